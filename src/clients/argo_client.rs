@@ -1,127 +1,278 @@
 use std::collections::HashMap;
-use openidconnect::core::{CoreClient, CoreProviderMetadata, CoreResponseType};
+use std::str::FromStr;
+use openidconnect::core::{CoreClient, CoreIdToken, CoreProviderMetadata, CoreResponseType};
 use openidconnect::{AuthenticationFlow, ClientId, CsrfToken, IssuerUrl, Nonce, PkceCodeChallenge, RedirectUrl, Scope};
-use reqwest::Client;
-use url::Url;
-use crate::models::argo::{AppInfo, ArgoCdInstance, ArgoTokenResponse, ArgoApplicationList, ArgocdSettings, ArgoSensorResource, SensorManifest};
-use crate::clients::{auth_success_response, extract_query_param};
+use chrono::Utc;
+use reqwest::{Certificate, Client, Method};
+use crate::models::argo::{extract_image_tag, extract_manifest_image_tag, AppInfo, ArgoApplication, ArgoCdInstance, ArgoManifestResponse, ArgoTokenResponse, ArgoApplicationList, ArgocdSettings, ArgoResourceManifest, ArgoResourceNode, ArgoResourceTree};
+use crate::models::config::{ArgoConfig, ArgoImageRule, ArgoInstanceConfig, ArgoResourceIdentity};
+use crate::clients::oidc_loopback::LoopbackServer;
 use crate::models::errors::ArcError;
 use crate::keyrings::argo_keyring::ArgoKeyring;
 
+// Refresh tokens slightly before they expire so that a request doesn't fail part way through
+const REFRESH_THRESHOLD_SECS: i64 = 60;
+
 /// Client that wraps ArgoCD API calls and handles token expiration
 pub struct ArgoClient {
     instance: ArgoCdInstance,
     client: Client,
     keyring: ArgoKeyring,
+    config: ArgoConfig,
 }
 
 impl ArgoClient {
-    pub fn new(instance: ArgoCdInstance) -> Result<Self, ArcError> {
-        // Create a re-usable HTTP client that accepts invalid TLS certs since many ArgoCD instances use self-signed certs
-        let client = Client::builder()
-            .danger_accept_invalid_certs(true)
-            .build()?;
+    pub fn new(instance: ArgoCdInstance, config: &ArgoConfig) -> Result<Self, ArcError> {
+        // Create a re-usable HTTP client, which is also used for OIDC discovery and token exchanges so
+        // that tokens are only ever sent over connections with the same trust settings
+        let client = http_client(instance, &config.instance(instance.name()))?;
         let keyring = ArgoKeyring::new(&instance);
 
-        Ok(Self { instance, client, keyring })
+        Ok(Self { instance, client, keyring, config: config.clone() })
     }
 
-    pub async fn fetch_apps(&self, project: &str) -> Result<HashMap<String, AppInfo>, ArcError> {
-        let argo_api_url = format!("{}/api/v1/applications?projects={project}", self.instance.base_url());
+    pub async fn fetch_apps(&self, projects: &[String]) -> Result<HashMap<String, AppInfo>, ArcError> {
+        let project_params: Vec<String> = projects.iter().map(|project| format!("projects={project}")).collect();
+        let argo_api_url = format!("{}/api/v1/applications?{}", self.instance.base_url(), project_params.join("&"));
         let resp = self.guarded_fetch(&argo_api_url).await?;
 
-        let mut apps: HashMap<String, AppInfo> = serde_json::from_str::<ArgoApplicationList>(&resp.to_string())?.items
-            .into_iter()
-            .map(|app| {
-                let app_info: AppInfo = app.into();
-                (app_info.name.clone(), app_info)
-            })
-            .collect();
-
-        // workflow-worker is a special case since it's a K8 Sensor resource
-        if let Some(worker_app) = apps.remove("workflow-worker") {
-            let namespace = self.instance.k8_namespace();
-            let worker_manifest = self.fetch_sensor_manifest("workflow-worker", namespace, "workflow-worker").await?;
-            let image_tag = worker_manifest.image_tag();
-            let updated_app = worker_app.with_image_tag(&image_tag);
-            apps.insert("workflow-worker".to_string(), updated_app);
+        let mut apps = HashMap::new();
+        for app in serde_json::from_str::<ArgoApplicationList>(&resp.to_string())?.items {
+            let resource = self.config.resource_identity(&app.metadata.name);
+            let mut app_info = AppInfo::new(app, &resource);
+
+            // Some resources, e.g. Sensors, don't report their images so they're read from the live manifest
+            if let Some(image_rule) = self.config.image_rule(&resource) {
+                let image_tag = self.fetch_rule_image_tag(&app_info.name, &resource, image_rule).await?;
+                app_info = app_info.with_image_tag(&image_tag);
+            }
+            apps.insert(app_info.name.clone(), app_info);
         }
 
         Ok(apps)
     }
 
-    pub async fn fetch_sensor_manifest(&self, app_name: &str, namespace: &str, sensor_name: &str) -> Result<SensorManifest, ArcError> {
+    async fn fetch_rule_image_tag(
+        &self,
+        app_name: &str,
+        resource: &ArgoResourceIdentity,
+        image_rule: &ArgoImageRule,
+    ) -> Result<String, ArcError> {
+        let manifest = self.fetch_resource_manifest(
+            app_name,
+            self.instance.k8_namespace(),
+            &resource.name,
+            &image_rule.version,
+            &resource.kind,
+            &resource.group,
+        ).await?;
+
+        Ok(extract_image_tag(&manifest, &image_rule.image_path).unwrap_or_else(|| "unknown".to_string()))
+    }
+
+    /// Fetches the resources managed by an application, along with the health of each
+    pub async fn fetch_resource_tree(&self, app_name: &str) -> Result<ArgoResourceTree, ArcError> {
+        let argo_api_url = format!("{}/api/v1/applications/{}/resource-tree", self.instance.base_url(), app_name);
+        let resp = self.guarded_fetch(&argo_api_url).await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    /// Fetches the live manifest of a resource managed by an application
+    pub async fn fetch_live_manifest(&self, app_name: &str, node: &ArgoResourceNode) -> Result<serde_json::Value, ArcError> {
+        self.fetch_resource_manifest(app_name, &node.namespace, &node.name, &node.version, &node.kind, &node.group).await
+    }
+
+    async fn fetch_resource_manifest(
+        &self,
+        app_name: &str,
+        namespace: &str,
+        name: &str,
+        version: &str,
+        kind: &str,
+        group: &str,
+    ) -> Result<serde_json::Value, ArcError> {
         let argo_api_url = format!(
-            "{}/api/v1/applications/{}/resource?namespace={}&resourceName={}&version=v1alpha1&kind=Sensor&group=argoproj.io",
+            "{}/api/v1/applications/{}/resource?namespace={}&resourceName={}&version={}&kind={}&group={}",
             self.instance.base_url(),
             app_name,
             namespace,
-            sensor_name
+            name,
+            version,
+            kind,
+            group
         );
 
         let resp = self.guarded_fetch(&argo_api_url).await?;
-        let sensor_resource = serde_json::from_value::<ArgoSensorResource>(resp)?;
-        let manifest = serde_json::from_str::<SensorManifest>(&sensor_resource.manifest)?;
+        let resource = serde_json::from_value::<ArgoResourceManifest>(resp)?;
+        Ok(serde_json::from_str(&resource.manifest)?)
+    }
 
-        Ok(manifest)
+    /// Fetches the current state of a single application
+    pub async fn fetch_app(&self, app_name: &str) -> Result<ArgoApplication, ArcError> {
+        let argo_api_url = format!("{}/api/v1/applications/{}", self.instance.base_url(), app_name);
+        let resp = self.guarded_fetch(&argo_api_url).await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    /// Asks ArgoCD to compare an application against git. A hard refresh also regenerates cached manifests.
+    pub async fn refresh_app(&self, app_name: &str, hard: bool) -> Result<ArgoApplication, ArcError> {
+        let refresh_type = if hard { "hard" } else { "normal" };
+        let argo_api_url = format!("{}/api/v1/applications/{}?refresh={}", self.instance.base_url(), app_name, refresh_type);
+        let resp = self.guarded_fetch(&argo_api_url).await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    /// Starts a sync operation, which runs asynchronously in ArgoCD
+    pub async fn sync_app(&self, app_name: &str, prune: bool, dry_run: bool) -> Result<(), ArcError> {
+        let argo_api_url = format!("{}/api/v1/applications/{}/sync", self.instance.base_url(), app_name);
+        let body = serde_json::json!({ "prune": prune, "dryRun": dry_run });
+        self.guarded_request(Method::POST, &argo_api_url, Some(&body)).await?;
+        Ok(())
+    }
+
+    /// Starts a rollback to the revision deployed at the given history ID, which runs asynchronously in ArgoCD
+    pub async fn rollback_app(&self, app_name: &str, history_id: u64) -> Result<(), ArcError> {
+        let argo_api_url = format!("{}/api/v1/applications/{}/rollback", self.instance.base_url(), app_name);
+        let body = serde_json::json!({ "id": history_id, "prune": false });
+        self.guarded_request(Method::POST, &argo_api_url, Some(&body)).await?;
+        Ok(())
+    }
+
+    /// Renders the manifests of a git revision to find the image tag that was deployed with it
+    pub async fn fetch_revision_image_tag(&self, app_name: &str, revision: &str) -> Result<Option<String>, ArcError> {
+        let argo_api_url = format!(
+            "{}/api/v1/applications/{}/manifests?revision={}",
+            self.instance.base_url(),
+            app_name,
+            revision
+        );
+        let resp = self.guarded_fetch(&argo_api_url).await?;
+        let manifests = serde_json::from_value::<ArgoManifestResponse>(resp)?.manifests;
+        let resource = self.config.resource_identity(app_name);
+        let image_rule = self.config.image_rule(&resource);
+        Ok(extract_manifest_image_tag(&resource, image_rule, &manifests))
+    }
+
+    /// Makes sure a usable token is cached, logging in if needed, so that later requests can be
+    /// made concurrently without racing each other through the interactive login flow
+    pub async fn ensure_logged_in(&self) -> Result<(), ArcError> {
+        self.get_cached_token().await?;
+        Ok(())
     }
 
     async fn guarded_fetch(&self, url: &str) -> Result<serde_json::Value, ArcError> {
-        let token = match self.keyring.get_credentials() {
-            Ok(cached_credentials) => {
-                Ok(cached_credentials.id_token)
-            },
-            Err(_) => {
-                // Either no token in cache or it couldn't be deserialized
-                cliclack::log::warning("ArgoCD credentials not cached. Initiating login flow...")?;
-                self.login().await
-            }
-        }?;
+        self.guarded_request(Method::GET, url, None).await
+    }
+
+    async fn guarded_request(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&serde_json::Value>
+    ) -> Result<serde_json::Value, ArcError> {
+        let token = self.get_cached_token().await?;
 
-        let response = self.fetch(url, &token).await?;
+        let response = self.fetch(method.clone(), url, &token, body).await?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
+            let response_body = response.text().await.unwrap_or_default();
 
             if status == 401 {
-                // Token is expired, re-login
-                cliclack::log::warning("Cached ArgoCD credentials expired. Initiating login flow...")?;
-                let new_token = self.login().await?;
+                // The token was revoked or invalidated before it expired, so try to refresh it before logging in
+                let refresh_token = self.keyring.get_credentials().ok().and_then(|credentials| credentials.refresh_token);
+                let new_token = self.refresh_or_login(refresh_token).await?;
 
                 // Retry the request with the new token
-                let response = self.fetch(url, &new_token).await?;
+                let response = self.fetch(method, url, &new_token, body).await?;
                 if response.status().is_success() {
                     return Ok(response.json().await?);
                 }
             }
 
-            return Err(ArcError::UserInputError(format!("ArgoCD API error {}: {}", status, body)));
+            return Err(ArcError::UserInputError(format!("ArgoCD API error {}: {}", status, response_body)));
         }
 
         Ok(response.json().await?)
     }
 
-    async fn fetch(&self, url: &str, token: &str) -> Result<reqwest::Response, ArcError> {
-        let request = self.client.get(url)
-            .header(reqwest::header::USER_AGENT, "arcli-backend")
-            .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", token));
+    /// Returns a cached ID token, silently refreshing it if it's about to expire and
+    /// only falling back to an interactive login if that isn't possible
+    async fn get_cached_token(&self) -> Result<String, ArcError> {
+        let credentials = match self.keyring.get_credentials() {
+            Ok(cached_credentials) => cached_credentials,
+            Err(_) => {
+                // Either no token in cache or it couldn't be deserialized
+                cliclack::log::warning("ArgoCD credentials not cached. Initiating login flow...")?;
+                return self.login().await;
+            }
+        };
 
-        let response = request.send().await?;
-        Ok(response)
+        let now = Utc::now();
+        if credentials.expires_at > now + chrono::Duration::seconds(REFRESH_THRESHOLD_SECS) {
+            return Ok(credentials.id_token);
+        }
+
+        self.refresh_or_login(credentials.refresh_token).await
     }
 
-    async fn login(&self) -> Result<String, ArcError> {
-        // Start a local HTTP server to listen for the OIDC callback
-        let redirect_host = "localhost:8085";
-        let redirect_uri = format!("http://{}/auth/callback", redirect_host);
-        let http_server = tiny_http::Server::http(redirect_host)?;
+    /// Replaces an expired or rejected ID token, only logging in interactively if it can't be refreshed
+    async fn refresh_or_login(&self, refresh_token: Option<String>) -> Result<String, ArcError> {
+        let Some(refresh_token) = refresh_token else {
+            cliclack::log::warning("Cached ArgoCD credentials expired. Initiating login flow...")?;
+            return self.login().await;
+        };
+
+        match self.refresh(&refresh_token).await {
+            Ok(id_token) => Ok(id_token),
+            Err(e) => {
+                cliclack::log::warning(format!("Unable to refresh ArgoCD credentials ({}). Initiating login flow...", e))?;
+                self.login().await
+            }
+        }
+    }
+
+    /// Exchanges a refresh token for a new ID token, without any user interaction
+    async fn refresh(&self, refresh_token: &str) -> Result<String, ArcError> {
+        let (provider_metadata, client_id, token_endpoint_url) = self.discover_provider(&self.client).await?;
+
+        let token_params = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", client_id.as_str()),
+        ];
+
+        let token_response = self.client
+            .post(&token_endpoint_url)
+            .form(&token_params)
+            .send()
+            .await?;
+        if !token_response.status().is_success() {
+            let status = token_response.status();
+            let body = token_response.text().await.unwrap_or_default();
+            return Err(ArcError::UserInputError(format!("Token refresh failed with {}: {}", status, body)));
+        }
 
-        // Create an HTTP client that accepts self-signed certificates (--insecure)
-        let http_client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(true)
-            .build()?;
+        let token_data: ArgoTokenResponse = token_response.json().await?;
+        let id_token = token_data.id_token
+            .ok_or_else(|| ArcError::UserInputError("No ID token in refresh response".to_string()))?;
+
+        // Refreshed ID tokens don't carry the nonce from the original login, but are otherwise verified as usual
+        let oidc_client = CoreClient::from_provider_metadata(provider_metadata, ClientId::new(client_id), None);
+        let parsed_id_token = CoreIdToken::from_str(&id_token)
+            .map_err(|e| ArcError::UserInputError(format!("Unable to parse ID token: {}", e)))?;
+        parsed_id_token.claims(&oidc_client.id_token_verifier(), |_: Option<&Nonce>| Ok(()))?;
+
+        // Providers that don't rotate refresh tokens omit it from the response, so keep using the current one
+        let refresh_token = token_data.refresh_token.or_else(|| Some(refresh_token.to_string()));
+        self.keyring.save_credentials(&id_token, refresh_token, token_data.expires_in)?;
+
+        Ok(id_token)
+    }
 
+    /// Looks up ArgoCD's OIDC settings and discovers the provider's metadata, including the JWKS used
+    /// to verify ID token signatures. Returns the metadata, the CLI's client ID and the token endpoint.
+    async fn discover_provider(&self, http_client: &Client) -> Result<(CoreProviderMetadata, String, String), ArcError> {
         // Fetch ArgoCD's OIDC settings
         let settings_url = format!("{}/api/v1/settings", self.instance.base_url());
         let settings_response = http_client.get(&settings_url).send().await?;
@@ -146,8 +297,7 @@ impl ArgoClient {
             .cli_client_id
             .ok_or_else(|| ArcError::UserInputError("No OIDC client ID in ArgoCD settings".to_string()))?;
 
-        // Discover OIDC provider metadata
-        let provider_metadata = CoreProviderMetadata::discover_async(issuer_url, &http_client).await?;
+        let provider_metadata = CoreProviderMetadata::discover_async(issuer_url, http_client).await?;
 
         let token_endpoint_url = provider_metadata
             .token_endpoint()
@@ -155,6 +305,35 @@ impl ArgoClient {
             .url()
             .to_string();
 
+        Ok((provider_metadata, client_id, token_endpoint_url))
+    }
+
+    async fn fetch(
+        &self,
+        method: Method,
+        url: &str,
+        token: &str,
+        body: Option<&serde_json::Value>
+    ) -> Result<reqwest::Response, ArcError> {
+        let mut request = self.client.request(method, url)
+            .header(reqwest::header::USER_AGENT, "arcli-backend")
+            .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", token));
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await?;
+        Ok(response)
+    }
+
+    async fn login(&self) -> Result<String, ArcError> {
+        // Start a local HTTP server to listen for the OIDC callback, on the port ArgoCD's CLI client expects
+        let loopback = LoopbackServer::bind("localhost", 8085, "/auth/callback", "ArgoCD")?;
+        let redirect_uri = loopback.redirect_uri().to_string();
+
+        let http_client = &self.client;
+        let (provider_metadata, client_id, token_endpoint_url) = self.discover_provider(http_client).await?;
+
         // Create OIDC client
         let oidc_client = CoreClient::from_provider_metadata(
             provider_metadata,
@@ -166,7 +345,7 @@ impl ArgoClient {
         let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
 
         // Build authorization URL
-        let (auth_url, _csrf_token, _nonce) = oidc_client
+        let (auth_url, csrf_token, nonce) = oidc_client
             .authorize_url(
                 AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
                 CsrfToken::new_random,
@@ -182,37 +361,77 @@ impl ArgoClient {
         // Open the user's browser for authentication
         webbrowser::open(auth_url.as_str())?;
 
-        // Wait for the OIDC callback
-        let request = http_server.recv()?;
-        let request_url = Url::parse(&format!("http://localhost{}", request.url()))?;
-        let code = extract_query_param(&request_url, "code")?;
+        // Wait for the OIDC callback, which must carry the same state as the auth request
+        let callback = loopback.wait_for_callback(csrf_token.secret())?;
 
         // Exchange authorization code for tokens
         let token_params = vec![
             ("grant_type", "authorization_code"),
-            ("code", code.as_str()),
+            ("code", callback.code.as_str()),
             ("redirect_uri", redirect_uri.as_str()),
             ("client_id", oidc_client.client_id().as_str()),
             ("code_verifier", pkce_verifier.secret()),
         ];
 
-        let token_response = http_client
-            .post(&token_endpoint_url)
-            .form(&token_params)
-            .send()
-            .await?;
+        let token_result = async {
+            let token_response = http_client
+                .post(&token_endpoint_url)
+                .form(&token_params)
+                .send()
+                .await?;
+            if !token_response.status().is_success() {
+                let status = token_response.status();
+                let body = token_response.text().await.unwrap_or_default();
+                return Err(ArcError::UserInputError(format!("Token exchange failed with {}: {}", status, body)));
+            }
 
-        let token_data: ArgoTokenResponse = token_response.json().await?;
-        let id_token = token_data.id_token
-            .ok_or_else(|| ArcError::UserInputError("No ID token in response".to_string()))?;
+            let token_data: ArgoTokenResponse = token_response.json().await?;
+            let id_token = token_data.id_token.clone()
+                .ok_or_else(|| ArcError::UserInputError("No ID token in response".to_string()))?;
+
+            // Verify the ID token's signature, issuer, audience and expiry, and that it was issued for this login
+            let verifier = oidc_client.id_token_verifier();
+            let parsed_id_token = CoreIdToken::from_str(&id_token)
+                .map_err(|e| ArcError::UserInputError(format!("Unable to parse ID token: {}", e)))?;
+            parsed_id_token.claims(&verifier, &nonce)?;
+
+            Ok::<_, ArcError>((id_token, token_data))
+        }.await;
+
+        // Let the browser know whether the login succeeded
+        let (id_token, token_data) = match token_result {
+            Ok(result) => result,
+            Err(e) => return Err(callback.respond_error(e)),
+        };
+        callback.respond_success()?;
 
         // Save ArgoCD credentials to the keyring
         self.keyring.save_credentials(&id_token, token_data.refresh_token, token_data.expires_in)?;
 
-        // Respond to the browser
-        let response = auth_success_response("ArgoCD")?;
-        request.respond(response)?;
-
         Ok(id_token)
     }
 }
+
+/// Builds an HTTP client that verifies an ArgoCD instance's certificate against the system's trusted
+/// certificates and any configured CA bundle, unless verification has been explicitly disabled
+fn http_client(instance: ArgoCdInstance, instance_config: &ArgoInstanceConfig) -> Result<Client, ArcError> {
+    let mut builder = Client::builder();
+
+    if instance_config.insecure {
+        cliclack::log::warning(format!(
+            "TLS verification is disabled for ArgoCD {} (argo.instances.{}.insecure), credentials may be intercepted",
+            instance.name(),
+            instance.name()
+        ))?;
+        builder = builder.danger_accept_invalid_certs(true);
+    } else if let Some(ca_bundle) = instance_config.ca_bundle()? {
+        let pem = std::fs::read(&ca_bundle).map_err(|e| ArcError::invalid_config_error(
+            format!("Unable to read CA bundle {} for ArgoCD {}: {}", ca_bundle.display(), instance.name(), e)
+        ))?;
+        for certificate in Certificate::from_pem_bundle(&pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder.build()?)
+}
@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use reqwest::{Client, Method, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, ACCEPT, AUTHORIZATION, LINK, USER_AGENT};
+use serde::de::DeserializeOwned;
+use crate::models::errors::ArcError;
+use crate::models::github::{
+    GithubCommit, GithubCommitDetails, GithubFileContent, GithubPr, GithubPrFile, GithubRef, GithubRepo, GithubTree,
+    GithubWorkflowRun, GithubWorkflowRuns, Installation, InstallationToken,
+};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+pub const GITHUB_ORG: &str = "agilityrobotics";
+const PAGE_SIZE: usize = 100;
+
+// GitHub only lists up to this many files for a PR, no matter how many pages are requested
+const MAX_PR_FILES: usize = 3000;
+
+/// Repo whose PRs bump the image tags that ArgoCD deploys
+pub const GITOPS_REPO: &str = "services-gitops";
+
+/// Client that wraps GitHub API calls for repos in the agilityrobotics org
+pub struct GithubClient {
+    client: Client,
+    token: String,
+}
+
+impl GithubClient {
+    pub fn new(token: &str) -> Self {
+        Self { client: Client::new(), token: token.to_string() }
+    }
+
+    pub async fn fetch_pr_files(&self, repo: &str, pull_request: u32) -> Result<Vec<GithubPrFile>, ArcError> {
+        let path = format!("/repos/{GITHUB_ORG}/{repo}/pulls/{pull_request}/files?per_page={PAGE_SIZE}");
+        let files: Vec<GithubPrFile> = self.get_paginated(&path, |_| true).await?;
+
+        if files.len() >= MAX_PR_FILES {
+            cliclack::log::warning(format!(
+                "PR #{} changes at least {} files, which is as many as GitHub will list, so some changes may be missing",
+                pull_request,
+                MAX_PR_FILES
+            ))?;
+        }
+        Ok(files)
+    }
+
+    /// Fetches the open PRs that were created after the cutoff time, most recent first
+    pub async fn fetch_open_prs(&self, repo: &str, created_after: DateTime<Utc>) -> Result<Vec<GithubPr>, ArcError> {
+        let path = format!("/repos/{GITHUB_ORG}/{repo}/pulls?state=open&sort=created&direction=desc&per_page={PAGE_SIZE}");
+
+        // PRs are sorted by creation time, so there's no need to read past the first one that's too old
+        let prs: Vec<GithubPr> = self.get_paginated(&path, |page: &[GithubPr]| {
+            page.last().is_some_and(|pr| pr.created_at > created_after)
+        }).await?;
+        Ok(prs.into_iter().filter(|pr| pr.created_at > created_after).collect())
+    }
+
+    /// Fetches the PRs whose head is the given branch, most recently created first
+    pub async fn fetch_branch_prs(&self, repo: &str, branch: &str) -> Result<Vec<GithubPr>, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/pulls?state=all&head={GITHUB_ORG}:{branch}&sort=created&direction=desc")).await
+    }
+
+    /// Fetches the most recent commits reachable from a revision that touched the given path
+    pub async fn fetch_path_commits(&self, repo: &str, revision: &str, path: &str, limit: usize) -> Result<Vec<GithubCommit>, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/commits?sha={revision}&path={path}&per_page={limit}")).await
+    }
+
+    /// Fetches a commit along with the files that it changed
+    pub async fn fetch_commit(&self, repo: &str, sha: &str) -> Result<GithubCommitDetails, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/commits/{sha}")).await
+    }
+
+    /// Fetches the PRs that a commit belongs to, e.g. the PR that merged it
+    pub async fn fetch_commit_prs(&self, repo: &str, sha: &str) -> Result<Vec<GithubPr>, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/commits/{sha}/pulls")).await
+    }
+
+    /// Fetches the most recent runs of every workflow for a branch, most recent first
+    pub async fn fetch_workflow_runs(&self, repo: &str, branch: &str, limit: usize) -> Result<Vec<GithubWorkflowRun>, ArcError> {
+        let runs: GithubWorkflowRuns = self.get(&format!("/repos/{GITHUB_ORG}/{repo}/actions/runs?branch={branch}&per_page={limit}")).await?;
+        Ok(runs.workflow_runs)
+    }
+
+    pub async fn fetch_workflow_run(&self, repo: &str, run_id: u64) -> Result<GithubWorkflowRun, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/actions/runs/{run_id}")).await
+    }
+
+    /// Finds the ID of the GitHub App's installation in an org, where the client authenticates with an app JWT
+    pub async fn fetch_installation_id(&self, org: &str) -> Result<u64, ArcError> {
+        let installations: Vec<Installation> = self.get_paginated(&format!("/app/installations?per_page={PAGE_SIZE}"), |_| true).await?;
+
+        // The app may be installed in several orgs, so use the installation for the org being queried
+        installations
+            .iter()
+            .find(|i| i.account.login.eq_ignore_ascii_case(org))
+            .map(|i| i.id)
+            .ok_or_else(|| ArcError::UserInputError(format!("No GitHub App installation found for the {} org", org)))
+    }
+
+    /// Mints an access token for an installation of the GitHub App, where the client authenticates with an app JWT
+    pub async fn create_installation_token(&self, installation_id: u64) -> Result<InstallationToken, ArcError> {
+        let path = format!("/app/installations/{installation_id}/access_tokens");
+        let response = self.send_json(Method::POST, &path, &serde_json::json!({})).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn fetch_repo(&self, repo: &str) -> Result<GithubRepo, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}")).await
+    }
+
+    /// Fetches the SHA of the commit at the head of a branch
+    pub async fn fetch_branch_sha(&self, repo: &str, branch: &str) -> Result<String, ArcError> {
+        let git_ref: GithubRef = self.get(&format!("/repos/{GITHUB_ORG}/{repo}/git/ref/heads/{branch}")).await?;
+        Ok(git_ref.object.sha)
+    }
+
+    /// Fetches the path of every file and directory in the repo as of a commit
+    pub async fn fetch_tree_paths(&self, repo: &str, sha: &str) -> Result<Vec<String>, ArcError> {
+        let tree: GithubTree = self.get(&format!("/repos/{GITHUB_ORG}/{repo}/git/trees/{sha}?recursive=1")).await?;
+        if tree.truncated {
+            cliclack::log::warning(format!("{} has more files than GitHub will list at once, so some may be missing", repo))?;
+        }
+        Ok(tree.tree.into_iter().map(|entry| entry.path).collect())
+    }
+
+    pub async fn fetch_file(&self, repo: &str, path: &str, revision: &str) -> Result<GithubFileContent, ArcError> {
+        self.get(&format!("/repos/{GITHUB_ORG}/{repo}/contents/{path}?ref={revision}")).await
+    }
+
+    pub async fn create_branch(&self, repo: &str, branch: &str, sha: &str) -> Result<(), ArcError> {
+        let body = serde_json::json!({ "ref": format!("refs/heads/{branch}"), "sha": sha });
+        self.send_json(Method::POST, &format!("/repos/{GITHUB_ORG}/{repo}/git/refs"), &body).await?;
+        Ok(())
+    }
+
+    pub async fn delete_branch(&self, repo: &str, branch: &str) -> Result<(), ArcError> {
+        let url = format!("{GITHUB_API_URL}/repos/{GITHUB_ORG}/{repo}/git/refs/heads/{branch}");
+        self.send(self.client.delete(url)).await?;
+        Ok(())
+    }
+
+    /// Commits new content for a file to a branch, where `sha` is the blob SHA of the content being replaced
+    pub async fn update_file(
+        &self,
+        repo: &str,
+        branch: &str,
+        path: &str,
+        sha: &str,
+        content: &str,
+        message: &str,
+    ) -> Result<(), ArcError> {
+        let body = serde_json::json!({
+            "message": message,
+            "content": STANDARD.encode(content),
+            "sha": sha,
+            "branch": branch,
+        });
+        self.send_json(Method::PUT, &format!("/repos/{GITHUB_ORG}/{repo}/contents/{path}"), &body).await?;
+        Ok(())
+    }
+
+    pub async fn create_pr(&self, repo: &str, head: &str, base: &str, title: &str, body: &str) -> Result<GithubPr, ArcError> {
+        let body = serde_json::json!({ "title": title, "head": head, "base": base, "body": body });
+        let response = self.send_json(Method::POST, &format!("/repos/{GITHUB_ORG}/{repo}/pulls"), &body).await?;
+        Ok(response.json().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ArcError> {
+        let response = self.send(self.client.get(format!("{GITHUB_API_URL}{path}"))).await?;
+        Ok(response.json().await?)
+    }
+
+    async fn send_json(&self, method: Method, path: &str, body: &serde_json::Value) -> Result<Response, ArcError> {
+        self.send(self.client.request(method, format!("{GITHUB_API_URL}{path}")).json(body)).await
+    }
+
+    /// Follows the `Link` header through every page of a list, for as long as `more` returns true
+    /// for the latest page
+    async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        more: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>, ArcError> {
+        let mut items = Vec::new();
+        let mut next_url = Some(format!("{GITHUB_API_URL}{path}"));
+
+        while let Some(url) = next_url {
+            let response = self.send(self.client.get(&url)).await?;
+            next_url = next_page_url(response.headers());
+
+            let page: Vec<T> = response.json().await?;
+            if !more(&page) {
+                next_url = None;
+            }
+            items.extend(page);
+        }
+
+        Ok(items)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, ArcError> {
+        let response = request
+            .header(USER_AGENT, "arcli-backend")
+            .header(ACCEPT, "application/vnd.github.v3+json")
+            .header(AUTHORIZATION, format!("Bearer {}", self.token))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let response_body = response.text().await.unwrap_or_default();
+            return Err(ArcError::UserInputError(format!("GitHub API error {}: {}", status, response_body)));
+        }
+
+        Ok(response)
+    }
+}
+
+/// Extracts the URL of the next page from a `Link` header, e.g.
+/// `<https://api.github.com/...&page=2>; rel="next", <https://api.github.com/...&page=5>; rel="last"`
+fn next_page_url(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    link.split(',')
+        .filter_map(|part| part.split_once(';'))
+        .find(|(_, params)| params.split(';').any(|param| param.trim() == r#"rel="next""#))
+        .map(|(url, _)| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+}
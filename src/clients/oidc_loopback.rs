@@ -0,0 +1,240 @@
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+use tiny_http::{Request, Response, Server};
+use url::Url;
+use crate::models::errors::ArcError;
+
+// Long enough to sign in and complete MFA, short enough that an abandoned login doesn't hang forever
+const CALLBACK_TIMEOUT_SECS: u64 = 300;
+
+/// Local HTTP server that receives the authorization code from a browser based OAuth/OIDC login
+pub struct LoopbackServer {
+    server: Server,
+    redirect_uri: Url,
+    auth_type: String,
+}
+
+/// A validated callback request, which is held open so that the browser can be told whether
+/// the rest of the login (e.g. the token exchange) succeeded
+pub struct LoopbackCallback {
+    pub code: String,
+    request: Request,
+    auth_type: String,
+}
+
+impl LoopbackServer {
+    /// Listens on a fixed port, which is required when the identity provider only accepts a pre-registered
+    /// redirect URI. Use port 0 to let the OS pick any available port.
+    pub fn bind(host: &str, port: u16, callback_path: &str, auth_type: &str) -> Result<Self, ArcError> {
+        let server = Server::http((host, port)).map_err(|e| {
+            let in_use = e.downcast_ref::<std::io::Error>()
+                .is_some_and(|io_error| io_error.kind() == std::io::ErrorKind::AddrInUse);
+            if in_use {
+                ArcError::OidcCallbackError(format!(
+                    "Port {} is already in use, please finish or close any other {} login and retry", port, auth_type
+                ))
+            } else {
+                ArcError::OidcCallbackError(format!("Unable to start {} login server: {}", auth_type, e))
+            }
+        })?;
+
+        // Read back the port in case the OS picked it
+        let port = server.server_addr().to_ip()
+            .map(|addr| addr.port())
+            .ok_or_else(|| ArcError::OidcCallbackError("Unexpected login server address type".to_string()))?;
+        let redirect_uri = Url::parse(&format!("http://{}:{}{}", host, port, callback_path))?;
+
+        Ok(Self { server, redirect_uri, auth_type: auth_type.to_string() })
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        self.redirect_uri.as_str()
+    }
+
+    /// Waits for the identity provider to redirect the browser back with an authorization code. Callbacks
+    /// whose state doesn't match the state sent with the auth request (e.g. stray local requests or browser
+    /// prefetches) are answered with an error page and ignored, while a matching callback that reports an
+    /// error fails the login.
+    pub fn wait_for_callback(&self, expected_state: &str) -> Result<LoopbackCallback, ArcError> {
+        let deadline = Instant::now() + Duration::from_secs(CALLBACK_TIMEOUT_SECS);
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(request) = self.server.recv_timeout(remaining)? else {
+                return Err(ArcError::OidcCallbackError(format!(
+                    "Timed out after {} seconds waiting for {} login to complete", CALLBACK_TIMEOUT_SECS, self.auth_type
+                )));
+            };
+
+            // The request URL is relative, so resolve it against the redirect URI
+            let request_url = self.redirect_uri.join(request.url())?;
+
+            // Ignore anything other than the callback, e.g. browsers requesting a favicon
+            if request_url.path() != self.redirect_uri.path() {
+                let _ = request.respond(Response::empty(404));
+                continue;
+            }
+
+            // A missing or mismatched state means the callback wasn't for the login this CLI started
+            match extract_query_param(&request_url, "state") {
+                Ok(state) if state == expected_state => {},
+                _ => {
+                    let _ = request.respond(auth_error_response(&self.auth_type, "State parameter mismatch"));
+                    continue;
+                },
+            }
+
+            if let Ok(error) = extract_query_param(&request_url, "error") {
+                let description = extract_query_param(&request_url, "error_description")
+                    .map(|d| format!("{}: {}", error, d))
+                    .unwrap_or(error);
+                return Err(respond_with_error(request, &self.auth_type, description));
+            }
+
+            return match extract_query_param(&request_url, "code") {
+                Ok(code) => Ok(LoopbackCallback { code, request, auth_type: self.auth_type.clone() }),
+                Err(e) => Err(respond_with_error(request, &self.auth_type, e.to_string())),
+            };
+        }
+    }
+}
+
+impl LoopbackCallback {
+    pub fn respond_success(self) -> Result<(), ArcError> {
+        let response = auth_success_response(&self.auth_type)?;
+        self.request.respond(response)?;
+        Ok(())
+    }
+
+    /// Shows the error in the browser, then returns it so that it can be propagated
+    pub fn respond_error(self, error: ArcError) -> ArcError {
+        let _ = self.request.respond(auth_error_response(&self.auth_type, &error.to_string()));
+        error
+    }
+}
+
+pub(crate) fn extract_query_param(url: &Url, key: &str) -> Result<String, ArcError> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+        .ok_or_else(|| ArcError::UrlQueryParamError(url.clone(), key.to_string()))
+}
+
+fn respond_with_error(request: Request, auth_type: &str, message: String) -> ArcError {
+    let _ = request.respond(auth_error_response(auth_type, &message));
+    ArcError::OidcCallbackError(format!("{} login failed: {}", auth_type, message))
+}
+
+fn auth_success_response(auth_type: &str) -> Result<Response<Cursor<Vec<u8>>>, ArcError> {
+    // Embed the Digit robot drumming image from assets as base64
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+    let image_bytes = include_bytes!("../../assets/digit_drumming.jpg");
+    let image_base64 = STANDARD.encode(image_bytes);
+
+    let html = format!(r#"
+    <html>
+    <head>
+        <style>
+            body {{
+                font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif;
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                justify-content: center;
+                min-height: 100vh;
+                margin: 0;
+                background: url('data:image/jpeg;base64,{}') center/cover no-repeat;
+                color: white;
+            }}
+            .container {{
+                text-align: center;
+                background: linear-gradient(135deg, rgba(20, 184, 166, 0.9) 0%, rgba(13, 148, 136, 0.9) 100%);
+                padding: 3rem;
+                border-radius: 20px;
+                backdrop-filter: blur(10px);
+                box-shadow: 0 8px 32px 0 rgba(31, 38, 135, 0.37);
+            }}
+            h1 {{
+                margin: 0 0 1rem 0;
+                font-size: 2.5rem;
+            }}
+            p {{
+                font-size: 1.2rem;
+                margin: 1rem 0;
+            }}
+        </style>
+    </head>
+    <body>
+        <div class="container">
+            <h1>{} Authentication Successful</h1>
+            <h2>You're ready to rock!</h2>
+            <p>You can close this tab and return to the terminal.</p>
+        </div>
+    </body>
+    </html>
+    "#, image_base64, auth_type);
+
+    let response = Response::from_string(html)
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..])
+                .map_err(|_| ArcError::HttpHeaderError("Failed to create Content-Type header".to_string()))?
+        );
+    Ok(response)
+}
+
+fn auth_error_response(auth_type: &str, message: &str) -> Response<Cursor<Vec<u8>>> {
+    let html = format!(r#"
+    <html>
+    <head>
+        <style>
+            body {{
+                font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif;
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                justify-content: center;
+                min-height: 100vh;
+                margin: 0;
+                background: #1f2937;
+                color: white;
+            }}
+            .container {{
+                text-align: center;
+                background: linear-gradient(135deg, rgba(220, 38, 38, 0.9) 0%, rgba(185, 28, 28, 0.9) 100%);
+                padding: 3rem;
+                border-radius: 20px;
+                box-shadow: 0 8px 32px 0 rgba(31, 38, 135, 0.37);
+            }}
+            h1 {{
+                margin: 0 0 1rem 0;
+                font-size: 2.5rem;
+            }}
+            p {{
+                font-size: 1.2rem;
+                margin: 1rem 0;
+            }}
+        </style>
+    </head>
+    <body>
+        <div class="container">
+            <h1>{} Authentication Failed</h1>
+            <p>{}</p>
+            <p>You can close this tab and check the terminal for details.</p>
+        </div>
+    </body>
+    </html>
+    "#, auth_type, html_escape(message));
+
+    // A static header can't fail to parse
+    Response::from_string(html)
+        .with_status_code(400)
+        .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap())
+}
+
+/// Error messages can echo query params from the callback URL, so they mustn't be rendered as HTML
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
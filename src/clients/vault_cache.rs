@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::config_dir;
+use crate::models::errors::ArcError;
+use crate::models::vault::VaultInstance;
+
+// Listings are only cached briefly so that new secrets show up without having to clear the cache
+const CACHE_TTL_SECS: i64 = 300;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CacheContents {
+    listings: HashMap<String, CacheEntry>,
+    fields: HashMap<String, CacheEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntry {
+    cached_at: DateTime<Utc>,
+    values: Vec<String>,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self) -> bool {
+        Utc::now() < self.cached_at + chrono::Duration::seconds(CACHE_TTL_SECS)
+    }
+}
+
+/// Short-lived, on-disk cache of Vault path listings and secret field names (never secret values).
+/// The cache is best-effort, any failure to read or write it simply results in a cache miss.
+pub struct VaultCache {
+    file: Option<PathBuf>,
+    contents: Mutex<CacheContents>,
+}
+
+impl VaultCache {
+    pub fn new(instance: &VaultInstance, namespace: &str) -> Self {
+        let file = cache_file(instance, namespace).ok();
+        let contents = file.as_ref()
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .and_then(|json| serde_json::from_str::<CacheContents>(&json).ok())
+            .unwrap_or_default();
+
+        Self { file, contents: Mutex::new(contents) }
+    }
+
+    pub fn get_listing(&self, path: &str) -> Option<Vec<String>> {
+        let contents = self.contents.lock().ok()?;
+        contents.listings.get(path)
+            .filter(|entry| entry.is_fresh())
+            .map(|entry| entry.values.clone())
+    }
+
+    pub fn put_listing(&self, path: &str, children: &[String]) {
+        self.update(|contents| {
+            let entry = CacheEntry { cached_at: Utc::now(), values: children.to_vec() };
+            contents.listings.insert(path.to_string(), entry);
+        });
+    }
+
+    pub fn get_fields(&self, path: &str) -> Option<Vec<String>> {
+        let contents = self.contents.lock().ok()?;
+        contents.fields.get(path)
+            .filter(|entry| entry.is_fresh())
+            .map(|entry| entry.values.clone())
+    }
+
+    pub fn put_fields(&self, path: &str, fields: &[String]) {
+        self.update(|contents| {
+            let entry = CacheEntry { cached_at: Utc::now(), values: fields.to_vec() };
+            contents.fields.insert(path.to_string(), entry);
+        });
+    }
+
+    /// Discards all cached entries, e.g. after a secret has been created or deleted
+    pub fn clear(&self) {
+        self.update(|contents| *contents = CacheContents::default());
+    }
+
+    fn update(&self, modify: impl FnOnce(&mut CacheContents)) {
+        let Ok(mut contents) = self.contents.lock() else { return };
+        modify(&mut contents);
+
+        // Drop stale entries so that the cache file doesn't grow indefinitely
+        contents.listings.retain(|_, entry| entry.is_fresh());
+        contents.fields.retain(|_, entry| entry.is_fresh());
+
+        if let (Some(file), Ok(json)) = (&self.file, serde_json::to_string(&*contents)) {
+            let _ = std::fs::write(file, json);
+        }
+    }
+}
+
+/// Deletes the cache files for every namespace of a Vault instance, returning how many were deleted
+pub fn delete_cache_files(instance: &VaultInstance) -> Result<usize, ArcError> {
+    let mut dir = config_dir()?;
+    dir.push("cache");
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let prefix = format!("vault-{}-", instance.name());
+    let mut deleted = 0;
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let is_match = path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(&prefix));
+        if is_match {
+            std::fs::remove_file(path)?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+fn cache_file(instance: &VaultInstance, namespace: &str) -> Result<PathBuf, ArcError> {
+    let mut path = config_dir()?;
+    path.push("cache");
+    std::fs::create_dir_all(&path)?;
+
+    // Listings differ per namespace, so each one gets its own file
+    let namespace = namespace.replace('/', "_");
+    path.push(format!("vault-{}-{}.json", instance.name(), namespace));
+    Ok(path)
+}
@@ -1,100 +1,291 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use url::Url;
-use vaultrs::auth::oidc;
+use vaultrs::api::kv2::requests::{ReadSecretRequest, SetSecretRequestOptions};
+use vaultrs::api::token::responses::LookupTokenResponse;
+use vaultrs::auth::{approle, oidc};
 use vaultrs::client::VaultClientSettingsBuilder;
-use vaultrs::kv2;
+use vaultrs::error::ClientError;
+use vaultrs::{kv2, token};
 use crate::models::errors::ArcError;
-use crate::models::vault::VaultInstance;
-use crate::clients::{auth_success_response, extract_query_param};
+use crate::models::config::CliConfig;
+use crate::models::vault::{
+    LeaseOwner, TrackedLease, VaultAuthMethod, VaultDatabaseCredentials, VaultInstance, VaultLeaseResponse, VaultSecretVersion,
+    VaultSecretVersionInfo, VaultSettings,
+};
+use crate::clients::oidc_loopback::{extract_query_param, LoopbackServer};
+use crate::clients::vault_cache::VaultCache;
+use crate::clients::vault_leases::VaultLeases;
 use crate::keyrings::vault_keyring::VaultKeyring;
 use crate::models::aws_profile::AwsAccount;
 
+const REVOKE_LEASE_PATH: &str = "sys/leases/revoke";
+
+const VAULT_TOKEN_ENV_VAR: &str = "VAULT_TOKEN";
+const VAULT_ROLE_ID_ENV_VAR: &str = "VAULT_ROLE_ID";
+const VAULT_SECRET_ID_ENV_VAR: &str = "VAULT_SECRET_ID";
+
+// Renew cached tokens that will expire within this many seconds
+const RENEWAL_THRESHOLD_SECS: i64 = 300;
+
 /// Client that wraps Vault API calls and handles token expiration
 pub struct VaultClient {
     vault_instance: VaultInstance,
-    secrets_namespace: Option<String>,
+    settings: VaultSettings,
+    secrets_namespace: String,
     keyring: VaultKeyring,
+    cache: VaultCache,
 }
 
 impl VaultClient {
-    pub fn new(account: &AwsAccount) -> Self {
-        let vault_instance = account.vault_instance();
-        let secrets_namespace = vault_instance.secrets_namespace(account);
+    pub fn new(account: &AwsAccount, config: &CliConfig) -> Result<Self, ArcError> {
+        let vault_instance = account.vault_instance()?;
+        let settings = VaultSettings::new(&vault_instance, config)?;
+        let secrets_namespace = settings.secrets_namespace(&vault_instance, account)?;
         let keyring = VaultKeyring::new(&vault_instance);
+        let cache = VaultCache::new(&vault_instance, &secrets_namespace);
+
+        Ok(Self { vault_instance, settings, secrets_namespace, keyring, cache })
+    }
 
-        Self { vault_instance, secrets_namespace, keyring  }
+    pub fn instance(&self) -> &VaultInstance {
+        &self.vault_instance
     }
 
     pub async fn guarded_list_paths(&self, parent_path: &str) -> Result<Vec<String>, ArcError> {
-        let token = self.get_cached_token().await?;
+        if let Some(child_paths) = self.cache.get_listing(parent_path) {
+            return Ok(child_paths);
+        }
 
-        match self.list_paths(parent_path, &token).await {
-            Ok(paths) => Ok(paths),
-            Err(_) => {
-                // Assume error is due to token being expired and re-login
-                cliclack::log::warning("Cached Vault credentials expired or invalid. Initiating login flow...")?;
-                let new_token = self.login().await?;
+        let child_paths = self.guarded(parent_path, |token| async move {
+            self.list_paths(parent_path, &token).await
+        }).await?;
 
-                // Retry the request with the new token
-                self.list_paths(parent_path, &new_token).await
+        self.cache.put_listing(parent_path, &child_paths);
+        Ok(child_paths)
+    }
+
+    /// Recursively lists all secrets (i.e. leaf paths) beneath the given prefix
+    pub async fn guarded_list_tree(&self, prefix: &str) -> Result<Vec<String>, ArcError> {
+        let mut secrets = Vec::new();
+        let mut pending = vec![prefix.to_string()];
+
+        while let Some(parent_path) = pending.pop() {
+            for path in self.guarded_list_paths(&parent_path).await? {
+                if path.ends_with('/') {
+                    pending.push(path);
+                } else {
+                    secrets.push(path);
+                }
             }
         }
+
+        secrets.sort();
+        Ok(secrets)
+    }
+
+    /// Retrieves the names, but not the values, of all fields within a secret. Vault has no endpoint for
+    /// field names alone, so this reads the whole secret and discards its values.
+    pub async fn guarded_list_fields(&self, path: &str) -> Result<Vec<String>, ArcError> {
+        if let Some(fields) = self.cache.get_fields(path) {
+            return Ok(fields);
+        }
+
+        let fields: Vec<String> = self.guarded_read_secret_version(path, None).await?
+            .map(|secret| secret.data.into_keys().collect())
+            .unwrap_or_default();
+
+        self.cache.put_fields(path, &fields);
+        Ok(fields)
     }
 
     pub async fn guarded_read_secret(&self, path: &str) -> Result<String, ArcError> {
-        let token = self.get_cached_token().await?;
+        self.guarded(path, |token| async move { self.read_secret(path, &token).await }).await
+    }
 
-        match self.read_secret(path, &token).await {
-            Ok(secrets) => Ok(secrets),
-            Err(_) => {
-                // Assume error is due to token being expired and re-login
-                cliclack::log::warning("Cached Vault credentials expired or invalid. Initiating login flow...")?;
-                let new_token = self.login().await?;
+    pub async fn guarded_read_secret_field(&self, path: &str, field: &str) -> Result<String, ArcError> {
+        self.guarded(path, |token| async move { self.read_secret_field(path, field, &token).await }).await
+    }
 
-                // Retry the request with the new token
-                self.read_secret(path, &new_token).await
+    pub async fn guarded_read_secret_version(
+        &self,
+        path: &str,
+        version: Option<u64>,
+    ) -> Result<Option<VaultSecretVersion>, ArcError> {
+        self.guarded(path, |token| async move { self.read_secret_version(path, version, &token).await }).await
+    }
+
+    pub async fn guarded_read_secret_history(&self, path: &str) -> Result<Option<Vec<VaultSecretVersionInfo>>, ArcError> {
+        self.guarded(path, |token| async move { self.read_secret_history(path, &token).await }).await
+    }
+
+    pub async fn guarded_write_secret(
+        &self,
+        path: &str,
+        data: &BTreeMap<String, String>,
+        cas: u64,
+    ) -> Result<u64, ArcError> {
+        let version = self.guarded(path, |token| async move {
+            self.write_secret(path, data, cas, &token).await
+        }).await?;
+
+        // The write may have created a new path or changed the secret's fields
+        self.cache.clear();
+        Ok(version)
+    }
+
+    pub async fn guarded_delete_secret_version(&self, path: &str, version: u64) -> Result<(), ArcError> {
+        self.guarded(path, |token| async move {
+            self.delete_secret_version(path, version, &token).await
+        }).await?;
+
+        self.cache.clear();
+        Ok(())
+    }
+
+    /// Issues short-lived database credentials, tracking the lease so that it can be revoked later
+    pub async fn guarded_database_credentials(
+        &self,
+        role: &str,
+    ) -> Result<VaultLeaseResponse<VaultDatabaseCredentials>, ArcError> {
+        let path = format!("{}/creds/{}", self.settings.database_mount, role);
+        let credentials: VaultLeaseResponse<VaultDatabaseCredentials> = self.guarded(&path, |token| {
+            let path = &path;
+            async move { self.read_leased(path, &token).await }
+        }).await?;
+
+        VaultLeases::new()?.add(TrackedLease {
+            lease_id: credentials.lease_id.clone(),
+            vault_instance: self.vault_instance.name().to_string(),
+            namespace: Some(self.secrets_namespace.clone()),
+            expires_at: Utc::now() + chrono::Duration::seconds(credentials.lease_duration as i64),
+            owner: Some(LeaseOwner::current()),
+        })?;
+
+        Ok(credentials)
+    }
+
+    pub async fn guarded_revoke_lease(&self, lease_id: &str) -> Result<(), ArcError> {
+        self.guarded(REVOKE_LEASE_PATH, |token| async move { self.revoke_lease(lease_id, &token).await }).await?;
+        VaultLeases::new()?.remove(lease_id)
+    }
+
+    /// Revokes any leases from this Vault namespace that were left behind by sessions that exited
+    /// without cleaning up (e.g. because the terminal was closed). Leases of sessions that are still
+    /// running are left alone. Returns the number revoked.
+    /// Leases that can't be revoked stay tracked, so that they're retried next time.
+    pub async fn revoke_orphaned_leases(&self) -> Result<usize, ArcError> {
+        let orphaned: Vec<TrackedLease> = VaultLeases::new()?.active()?
+            .into_iter()
+            .filter(|lease| lease.vault_instance == self.vault_instance.name())
+            .filter(|lease| lease.namespace.as_ref() == Some(&self.secrets_namespace))
+            .filter(|lease| !lease.owner.as_ref().is_some_and(|owner| owner.is_running()))
+            .collect();
+
+        let mut revoked = 0;
+        for lease in &orphaned {
+            match self.guarded_revoke_lease(&lease.lease_id).await {
+                Ok(()) => revoked += 1,
+                Err(e) => cliclack::log::warning(format!("Unable to revoke orphaned lease {}: {}", lease.lease_id, e))?,
             }
         }
+        Ok(revoked)
     }
 
-    pub async fn guarded_read_secret_field(&self, path: &str, field: &str) -> Result<String, ArcError> {
+    async fn guarded<T, F, Fut>(&self, path: &str, request: F) -> Result<T, ArcError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, ArcError>>,
+    {
         let token = self.get_cached_token().await?;
 
-        match self.read_secret_field(path, field, &token).await {
-            Ok(secret_field) => Ok(secret_field),
-            Err(_) => {
-                // Assume error is due to token being expired and re-login
-                cliclack::log::warning("Cached Vault credentials expired or invalid. Initiating login flow...")?;
+        match request(token.clone()).await {
+            Err(ArcError::VaultError(ClientError::APIError { code: 403, .. })) => {
+                // Vault responds with a 403 both for expired tokens and for paths the token isn't
+                // authorized to access, so check whether the token itself is still valid
+                if self.is_token_valid(&token).await {
+                    return Err(ArcError::VaultPermissionDenied(path.to_string()));
+                }
+
+                cliclack::log::warning("Cached Vault credentials expired or revoked. Initiating login flow...")?;
                 let new_token = self.login().await?;
 
                 // Retry the request with the new token
-                self.read_secret_field(path, field, &new_token).await
-            }
+                request(new_token).await
+            },
+            Err(ArcError::VaultError(ClientError::APIError { code: 404, .. })) => {
+                Err(ArcError::VaultSecretNotFound(path.to_string()))
+            },
+            result => result,
         }
     }
 
     async fn get_cached_token(&self) -> Result<String, ArcError> {
-        match self.keyring.get_credentials() {
-            Ok(cached_credentials) => {
-                cliclack::log::info("Attempting to use cached Vault credentials.")?;
-                Ok(cached_credentials.client_token)
-            },
+        // Externally provided tokens are managed by whoever issued them, so they're never cached
+        if self.settings.auth_method == VaultAuthMethod::Token {
+            return std::env::var(VAULT_TOKEN_ENV_VAR).map_err(|_| ArcError::invalid_config_error(
+                format!("{} must be set when using the token auth method", VAULT_TOKEN_ENV_VAR)
+            ));
+        }
+
+        let credentials = match self.keyring.get_credentials() {
+            Ok(cached_credentials) => cached_credentials,
             Err(_) => {
                 // Either no token in cache or it couldn't be deserialized
                 cliclack::log::warning("Vault credentials not cached. Initiating login flow...")?;
-                self.login().await
+                return self.login().await;
+            }
+        };
+
+        let now = Utc::now();
+        if credentials.expires_at > now + chrono::Duration::seconds(RENEWAL_THRESHOLD_SECS) {
+            return Ok(credentials.client_token);
+        }
+
+        // Extend the token's TTL if it's about to expire, which avoids a browser login
+        if credentials.renewable && credentials.expires_at > now {
+            match self.renew_token(&credentials.client_token).await {
+                Ok(()) => {
+                    cliclack::log::info("Renewed cached Vault credentials.")?;
+                    return Ok(credentials.client_token);
+                },
+                Err(e) => cliclack::log::warning(format!("Unable to renew Vault credentials: {}", e))?,
             }
         }
+
+        cliclack::log::warning("Cached Vault credentials expired. Initiating login flow...")?;
+        self.login().await
+    }
+
+    async fn renew_token(&self, token: &str) -> Result<(), ArcError> {
+        // Tokens are issued in the auth namespace, so they must be renewed there as well
+        let client = create_vault_client(
+            &self.settings.address,
+            self.settings.auth_namespace.clone(),
+            Some(token.to_string()),
+        );
+
+        let auth_info = token::renew_self(&client, None).await?;
+
+        // Renewal may return a shorter TTL than requested if the token is close to its max TTL
+        self.keyring.save_credentials(token, auth_info.lease_duration, auth_info.renewable)?;
+        Ok(())
+    }
+
+    async fn is_token_valid(&self, token: &str) -> bool {
+        lookup_token(&self.settings, token).await.is_ok()
     }
 
     async fn list_paths(&self, parent_path: &str, token: &str) -> Result<Vec<String>, ArcError> {
         let client = create_vault_client(
-            self.vault_instance.address(),
-            self.secrets_namespace.clone(),
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
             Some(token.to_string()),
         );
 
-        let items = kv2::list(&client, "kv-v2", parent_path).await?;
+        let items = kv2::list(&client, &self.settings.kv_mount, parent_path).await?;
 
         // Collect all available sub-paths
         let child_paths: Vec<String> = items
@@ -107,12 +298,12 @@ impl VaultClient {
 
     async fn read_secret(&self, path: &str, token: &str) -> Result<String, ArcError> {
         let client = create_vault_client(
-            self.vault_instance.address(),
-            self.secrets_namespace.clone(),
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
             Some(token.to_string()),
         );
 
-        let secrets: HashMap<String, String> = kv2::read(&client, "kv-v2", path).await?;
+        let secrets: HashMap<String, String> = kv2::read(&client, &self.settings.kv_mount, path).await?;
 
         let all_fields = secrets.iter()
             .map(|(k, v)| format!("{}: {}", k, v))
@@ -124,12 +315,12 @@ impl VaultClient {
 
     async fn read_secret_field(&self, path: &str, field: &str, token: &str) -> Result<String, ArcError> {
         let client = create_vault_client(
-            self.vault_instance.address(),
-            self.secrets_namespace.clone(),
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
             Some(token.to_string()),
         );
 
-        let secrets: HashMap<String, String> = kv2::read(&client, "kv-v2", path).await?;
+        let secrets: HashMap<String, String> = kv2::read(&client, &self.settings.kv_mount, path).await?;
 
         let secret_field = secrets.get(field)
             .ok_or_else(|| ArcError::UserInputError(format!("Field '{}' not found in secret '{}'", field, path)))?
@@ -138,56 +329,217 @@ impl VaultClient {
         Ok(secret_field)
     }
 
+    async fn read_secret_version(
+        &self,
+        path: &str,
+        version: Option<u64>,
+        token: &str,
+    ) -> Result<Option<VaultSecretVersion>, ArcError> {
+        let client = create_vault_client(
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
+            Some(token.to_string()),
+        );
+
+        // Use the raw endpoint since kv2::read discards the version metadata that check-and-set needs
+        let endpoint = ReadSecretRequest::builder()
+            .mount(&self.settings.kv_mount)
+            .path(path)
+            .version(version)
+            .build()
+            .expect("Unable to build Vault read request");
+
+        match vaultrs::api::exec_with_result(&client, endpoint).await {
+            Ok(response) => {
+                let data: BTreeMap<String, String> = serde_json::from_value(response.data)?;
+                Ok(Some(VaultSecretVersion { data, version: response.metadata.version }))
+            },
+            // Vault also returns a 404 for versions that have been deleted or destroyed
+            Err(ClientError::APIError { code: 404, .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn read_secret_history(&self, path: &str, token: &str) -> Result<Option<Vec<VaultSecretVersionInfo>>, ArcError> {
+        let client = create_vault_client(
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
+            Some(token.to_string()),
+        );
+
+        let metadata = match kv2::read_metadata(&client, &self.settings.kv_mount, path).await {
+            Ok(metadata) => metadata,
+            Err(ClientError::APIError { code: 404, .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut versions = Vec::new();
+        for (version, info) in metadata.versions {
+            // Version numbers are returned as map keys, so they need to be parsed
+            let Ok(version) = version.parse::<u64>() else { continue };
+
+            // An empty deletion time means the version has not been deleted
+            let deletion_time = match info.deletion_time.as_str() {
+                "" => None,
+                t => Some(DateTime::parse_from_rfc3339(t)?.with_timezone(&Utc)),
+            };
+
+            versions.push(VaultSecretVersionInfo {
+                version,
+                created_time: DateTime::parse_from_rfc3339(&info.created_time)?.with_timezone(&Utc),
+                deletion_time,
+                destroyed: info.destroyed,
+            });
+        }
+
+        // Newest versions first
+        versions.sort_by_key(|v| std::cmp::Reverse(v.version));
+        Ok(Some(versions))
+    }
+
+    async fn write_secret(
+        &self,
+        path: &str,
+        data: &BTreeMap<String, String>,
+        cas: u64,
+        token: &str,
+    ) -> Result<u64, ArcError> {
+        let client = create_vault_client(
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
+            Some(token.to_string()),
+        );
+
+        // Check-and-set ensures that the write only succeeds if the secret is still at the version
+        // that was read (a cas of 0 only allows the write if the secret doesn't exist yet)
+        let options = SetSecretRequestOptions { cas: cas as u32 };
+        let metadata = kv2::set_with_options(&client, &self.settings.kv_mount, path, data, options).await
+            .map_err(|e| match e {
+                ClientError::APIError { code: 400, errors } if errors.iter().any(|e| e.contains("check-and-set")) => {
+                    ArcError::VaultConflict(path.to_string())
+                },
+                e => e.into(),
+            })?;
+
+        Ok(metadata.version)
+    }
+
+    async fn delete_secret_version(&self, path: &str, version: u64, token: &str) -> Result<(), ArcError> {
+        let client = create_vault_client(
+            &self.settings.address,
+            Some(self.secrets_namespace.clone()),
+            Some(token.to_string()),
+        );
+
+        // Delete the specific version that was read, rather than whatever happens to be latest
+        kv2::delete_versions(&client, &self.settings.kv_mount, path, vec![version]).await?;
+        Ok(())
+    }
+
+    async fn read_leased<T: DeserializeOwned>(&self, path: &str, token: &str) -> Result<VaultLeaseResponse<T>, ArcError> {
+        // Use a raw request since vaultrs discards the lease ID that's needed for revocation
+        let response = self.raw_request(reqwest::Method::GET, path, token)
+            .send()
+            .await?;
+
+        Ok(check_status(response).await?.json().await?)
+    }
+
+    async fn revoke_lease(&self, lease_id: &str, token: &str) -> Result<(), ArcError> {
+        let response = self.raw_request(reqwest::Method::PUT, REVOKE_LEASE_PATH, token)
+            .json(&serde_json::json!({ "lease_id": lease_id }))
+            .send()
+            .await?;
+
+        check_status(response).await?;
+        Ok(())
+    }
+
+    fn raw_request(&self, method: reqwest::Method, path: &str, token: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/v1/{}", self.settings.address, path);
+        reqwest::Client::new()
+            .request(method, url)
+            .header("X-Vault-Token", token)
+            .header("X-Vault-Namespace", &self.secrets_namespace)
+    }
+
     async fn login(&self) -> Result<String, ArcError> {
-        // Start a local HTTP server to listen for the OIDC callback
-        let redirect_host = "localhost:8250";
-        let redirect_uri = format!("http://{}/oidc/callback", redirect_host);
-        let server = tiny_http::Server::http(redirect_host)?;
+        match self.settings.auth_method {
+            VaultAuthMethod::Oidc => self.login_oidc().await,
+            VaultAuthMethod::AppRole => self.login_approle().await,
+            VaultAuthMethod::Token => Err(ArcError::invalid_config_error(
+                format!("The Vault token in {} is expired or invalid", VAULT_TOKEN_ENV_VAR)
+            )),
+        }
+    }
+
+    async fn login_approle(&self) -> Result<String, ArcError> {
+        let role_id = std::env::var(VAULT_ROLE_ID_ENV_VAR);
+        let secret_id = std::env::var(VAULT_SECRET_ID_ENV_VAR);
+        let (Ok(role_id), Ok(secret_id)) = (role_id, secret_id) else {
+            return Err(ArcError::invalid_config_error(format!(
+                "{} and {} must be set when using the approle auth method",
+                VAULT_ROLE_ID_ENV_VAR,
+                VAULT_SECRET_ID_ENV_VAR,
+            )));
+        };
+
+        let client = create_vault_client(
+            &self.settings.address,
+            self.settings.auth_namespace.clone(),
+            None
+        );
+        let token_auth = approle::login(&client, &self.settings.auth_mount, &role_id, &secret_id).await?;
+
+        let token = token_auth.client_token;
+
+        // Save Vault credentials to the keyring
+        self.keyring.save_credentials(&token, token_auth.lease_duration, token_auth.renewable)?;
+
+        Ok(token)
+    }
+
+    async fn login_oidc(&self) -> Result<String, ArcError> {
+        // Start a local HTTP server to listen for the OIDC callback, on the port Vault's roles allow by default
+        let loopback = LoopbackServer::bind("localhost", 8250, "/oidc/callback", "Vault")?;
+        let redirect_uri = loopback.redirect_uri().to_string();
 
         // Retrieve the OIDC auth URL from Vault
         let client = create_vault_client(
-            self.vault_instance.address(),
-            self.vault_instance.oidc_namespace(),
+            &self.settings.address,
+            self.settings.auth_namespace.clone(),
             None
         );
         let auth_response = oidc::auth(
             &client,
-            "oidc", // mount path
+            &self.settings.auth_mount,
             &redirect_uri,
-            self.vault_instance.oidc_role(),
+            Some(self.settings.oidc_role.clone()),
         ).await?;
 
-        // Extract nonce from auth URL
+        // Extract the nonce and state generated by Vault from the auth URL
         let url = Url::parse(&auth_response.auth_url)?;
         let nonce = extract_query_param(&url, "nonce")?;
+        let state = extract_query_param(&url, "state")?;
 
         // Open the user's default web browser to the auth URL
         webbrowser::open(&auth_response.auth_url)?;
 
-        // Wait for the OIDC callback request
-        let request = server.recv()?;
-
-        // The request URL is relative, so we need to construct the absolute URL
-        let base_url_str = format!("http://{}/", redirect_host);
-        let base_url = Url::parse(&base_url_str)?;
-        let absolute_request_url = base_url.join(request.url())?;
+        // Wait for the OIDC callback request, which must carry the state Vault generated
+        let callback = loopback.wait_for_callback(&state)?;
 
-        // Extract code and state from the request URL
-        let code = extract_query_param(&absolute_request_url, "code")?;
-        let state = extract_query_param(&absolute_request_url, "state")?;
-
-        // Respond to the user in the browser
-        let response = auth_success_response("Vault")?;
-        request.respond(response)?;
-
-        // Complete the login with Vault using the captured parameters
-        let token_auth = oidc::callback(
+        // Complete the login with Vault, which verifies the ID token and nonce
+        let token_auth = match oidc::callback(
             &client,
-            "oidc",
+            &self.settings.auth_mount,
             state.as_str(),
             nonce.as_str(),
-            code.as_str()
-        ).await?;
+            callback.code.as_str()
+        ).await {
+            Ok(token_auth) => token_auth,
+            Err(e) => return Err(callback.respond_error(e.into())),
+        };
+        callback.respond_success()?;
 
         let token = token_auth.client_token;
 
@@ -198,6 +550,17 @@ impl VaultClient {
     }
 }
 
+/// Looks up the identity and remaining TTL of a token, which also verifies that it's still valid
+pub async fn lookup_token(settings: &VaultSettings, token: &str) -> Result<LookupTokenResponse, ArcError> {
+    let client = create_vault_client(&settings.address, settings.auth_namespace.clone(), Some(token.to_string()));
+    Ok(token::lookup_self(&client).await?)
+}
+
+pub async fn revoke_token(settings: &VaultSettings, token: &str) -> Result<(), ArcError> {
+    let client = create_vault_client(&settings.address, settings.auth_namespace.clone(), Some(token.to_string()));
+    Ok(token::revoke_self(&client).await?)
+}
+
 pub fn create_vault_client(
     address: &str,
     namespace: Option<String>,
@@ -212,3 +575,18 @@ pub fn create_vault_client(
 
     vaultrs::client::VaultClient::new(settings).expect("Vault Client creation failed")
 }
+
+/// Converts an unsuccessful response into the same error that vaultrs would have returned
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, ArcError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    let errors = body["errors"].as_array()
+        .map(|errors| errors.iter().filter_map(|e| e.as_str()).map(String::from).collect())
+        .unwrap_or_default();
+
+    Err(ClientError::APIError { code: status.as_u16(), errors }.into())
+}
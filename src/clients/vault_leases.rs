@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use chrono::Utc;
+use crate::{config_dir, write_private_file};
+use crate::models::errors::ArcError;
+use crate::models::vault::TrackedLease;
+
+const LOCK_TIMEOUT_SECS: u64 = 5;
+const LOCK_RETRY_MILLIS: u64 = 50;
+
+// Updates take milliseconds, so an older lock file was left behind by a process that crashed
+const LOCK_STALE_SECS: u64 = 30;
+
+/// On-disk record of the Vault leases issued to this CLI, so that leases orphaned by a crashed
+/// session can still be revoked the next time credentials are requested
+pub struct VaultLeases {
+    file: PathBuf,
+}
+
+impl VaultLeases {
+    pub fn new() -> Result<Self, ArcError> {
+        let mut file = config_dir()?;
+        file.push("vault-leases.json");
+        Ok(Self { file })
+    }
+
+    /// Returns all tracked leases that have not yet expired
+    pub fn active(&self) -> Result<Vec<TrackedLease>, ArcError> {
+        if !self.file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = std::fs::read_to_string(&self.file)?;
+        let leases: Vec<TrackedLease> = serde_json::from_str(&json)?;
+
+        let now = Utc::now();
+        Ok(leases.into_iter().filter(|lease| lease.expires_at > now).collect())
+    }
+
+    pub fn add(&self, lease: TrackedLease) -> Result<(), ArcError> {
+        let _lock = self.lock()?;
+        let mut leases = self.active()?;
+        leases.push(lease);
+        self.save(&leases)
+    }
+
+    pub fn remove(&self, lease_id: &str) -> Result<(), ArcError> {
+        let _lock = self.lock()?;
+        let mut leases = self.active()?;
+        leases.retain(|lease| lease.lease_id != lease_id);
+        self.save(&leases)
+    }
+
+    fn save(&self, leases: &[TrackedLease]) -> Result<(), ArcError> {
+        let json = serde_json::to_string_pretty(leases)?;
+        write_private_file(&self.file, &json)
+    }
+
+    /// Takes an exclusive lock on the file, so that concurrent sessions don't overwrite each other's leases
+    fn lock(&self) -> Result<LeasesLock, ArcError> {
+        let path = self.file.with_extension("lock");
+        let deadline = Instant::now() + Duration::from_secs(LOCK_TIMEOUT_SECS);
+        loop {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(LeasesLock { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let is_stale = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                        .is_some_and(|age| age > Duration::from_secs(LOCK_STALE_SECS));
+                    if is_stale {
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(ArcError::CommandExecutionError(format!(
+                            "Timed out waiting for {} to be unlocked", self.file.display()
+                        )));
+                    }
+                    std::thread::sleep(Duration::from_millis(LOCK_RETRY_MILLIS));
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Lock on the leases file, released when dropped
+struct LeasesLock {
+    path: PathBuf,
+}
+
+impl Drop for LeasesLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
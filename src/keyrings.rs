@@ -1,2 +1,4 @@
 pub mod argo_keyring;
+pub mod credential_store;
+pub mod github_keyring;
 pub mod vault_keyring;
\ No newline at end of file
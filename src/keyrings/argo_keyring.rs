@@ -1,8 +1,8 @@
 use chrono::{DateTime, Utc};
-use keyring::Entry;
 use serde::{Deserialize, Serialize};
 use crate::models::argo::ArgoCdInstance;
 use crate::models::errors::ArcError;
+use crate::keyrings::credential_store::credential_store;
 
 const KEYRING_SERVICE: &str = "arcli-backend-argo";
 
@@ -13,7 +13,8 @@ pub(crate) struct ArgoCredentials {
     pub expires_at: DateTime<Utc>,
 }
 
-/// Client that wraps access to operating system keyring's such as Keychain Access on MacOS
+/// Client that wraps access to the configured credential store, typically an operating system
+/// keyring such as Keychain Access on MacOS
 pub struct ArgoKeyring {
     service: String,
     user: String,
@@ -28,8 +29,8 @@ impl ArgoKeyring {
     }
 
     pub fn get_credentials(&self) -> Result<ArgoCredentials, ArcError> {
-        let entry = Entry::new(&self.service, &self.user)?;
-        let keyring_data = entry.get_password()?;
+        let keyring_data = credential_store().get(&self.service, &self.user)?
+            .ok_or(keyring::Error::NoEntry)?;
         Ok(serde_json::from_str::<ArgoCredentials>(&keyring_data)?)
     }
 
@@ -43,7 +44,11 @@ impl ArgoKeyring {
         let credentials = ArgoCredentials { id_token: id_token.to_string(), refresh_token, expires_at };
         let credentials_json = serde_json::to_string(&credentials)?;
 
-        let entry = Entry::new(&self.service, &self.user)?;
-        Ok(entry.set_password(&credentials_json)?)
+        credential_store().set(&self.service, &self.user, &credentials_json)
+    }
+
+    /// Removes any cached credentials, returning false if there were none
+    pub fn delete_credentials(&self) -> Result<bool, ArcError> {
+        credential_store().delete(&self.service, &self.user)
     }
 }
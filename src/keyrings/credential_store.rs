@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use keyring::Entry;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use crate::{config_dir, write_private_file};
+use crate::models::config::{CredentialStoreKind, CredentialsConfig};
+use crate::models::errors::ArcError;
+
+const PASSPHRASE_ENV_VAR: &str = "ARCLI_CREDENTIALS_PASSPHRASE";
+const MACHINE_ID_PATHS: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+static CREDENTIAL_STORE: OnceLock<Box<dyn CredentialStore>> = OnceLock::new();
+
+/// Storage for cached credentials (e.g. access tokens), keyed by service and user
+pub trait CredentialStore: Send + Sync {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>, ArcError>;
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<(), ArcError>;
+
+    /// Removes a credential, returning false if it didn't exist
+    fn delete(&self, service: &str, user: &str) -> Result<bool, ArcError>;
+}
+
+/// Selects the credential store to use for the remainder of the process. Must be called before
+/// any credentials are accessed, otherwise the store is chosen automatically.
+pub fn init_credential_store(config: &CredentialsConfig) -> Result<(), ArcError> {
+    let store = create_store(&config.store)?;
+    let _ = CREDENTIAL_STORE.set(store);
+    Ok(())
+}
+
+pub fn credential_store() -> &'static dyn CredentialStore {
+    CREDENTIAL_STORE
+        .get_or_init(|| create_store(&CredentialStoreKind::Auto).unwrap_or_else(|e| {
+            let _ = cliclack::log::warning(format!(
+                "Unable to set up a credential store ({}), so credentials won't be saved after this command", e
+            ));
+            Box::new(MemoryStore::default())
+        }))
+        .as_ref()
+}
+
+fn create_store(kind: &CredentialStoreKind) -> Result<Box<dyn CredentialStore>, ArcError> {
+    match kind {
+        CredentialStoreKind::Keyring => Ok(Box::new(OsKeyringStore)),
+        CredentialStoreKind::File => Ok(Box::new(EncryptedFileStore::new()?)),
+        CredentialStoreKind::Memory => Ok(Box::new(MemoryStore::default())),
+        CredentialStoreKind::Auto => {
+            if OsKeyringStore::is_available() {
+                Ok(Box::new(OsKeyringStore))
+            } else {
+                Ok(Box::new(EncryptedFileStore::new()?))
+            }
+        },
+    }
+}
+
+/// Operating system keyring, such as Keychain Access on MacOS or Secret Service on Linux
+pub struct OsKeyringStore;
+
+impl OsKeyringStore {
+    /// Headless Linux machines and containers often have no keyring daemon, in which case
+    /// every operation fails with a platform error rather than a missing entry
+    fn is_available() -> bool {
+        match Entry::new("arcli-backend-probe", "probe").and_then(|entry| entry.get_password()) {
+            Ok(_) | Err(keyring::Error::NoEntry) => true,
+            Err(_) => false,
+        }
+    }
+}
+
+impl CredentialStore for OsKeyringStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>, ArcError> {
+        match Entry::new(service, user)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<(), ArcError> {
+        Ok(Entry::new(service, user)?.set_password(secret)?)
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<bool, ArcError> {
+        match Entry::new(service, user)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Credentials held only for the lifetime of the process, for machines where nothing may be persisted
+#[derive(Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl CredentialStore for MemoryStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>, ArcError> {
+        Ok(self.entries.lock().map_err(lock_error)?.get(&entry_key(service, user)).cloned())
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<(), ArcError> {
+        self.entries.lock().map_err(lock_error)?.insert(entry_key(service, user), secret.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<bool, ArcError> {
+        Ok(self.entries.lock().map_err(lock_error)?.remove(&entry_key(service, user)).is_some())
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct EncryptedFile {
+    salt: String,
+    entries: HashMap<String, EncryptedEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedEntry {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// AES-256-GCM encrypted file under the config directory. The key is derived from the
+/// ARCLI_CREDENTIALS_PASSPHRASE environment variable if set, otherwise from the machine ID.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+
+    /// Key derivation is deliberately slow, so the derived key is kept along with the salt it was derived from
+    derived_key: Mutex<Option<(String, [u8; KEY_LEN])>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new() -> Result<Self, ArcError> {
+        let mut path = config_dir()?;
+        path.push("credentials.json");
+        Ok(Self { path, lock: Mutex::new(()), derived_key: Mutex::new(None) })
+    }
+
+    fn read(&self) -> Result<EncryptedFile, ArcError> {
+        if !self.path.exists() {
+            // A new salt is generated for each credentials file
+            let mut salt = [0u8; SALT_LEN];
+            SystemRandom::new().fill(&mut salt).map_err(|_| crypto_error("Unable to generate salt"))?;
+            return Ok(EncryptedFile { salt: STANDARD.encode(salt), entries: HashMap::new() });
+        }
+
+        let json = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Even though the contents are encrypted, access is restricted to the current user. The file is
+    /// replaced atomically, since a partial write would lose every stored credential.
+    fn write(&self, file: &EncryptedFile) -> Result<(), ArcError> {
+        write_private_file(&self.path, &serde_json::to_string_pretty(file)?)
+    }
+
+    fn key(&self, file: &EncryptedFile) -> Result<LessSafeKey, ArcError> {
+        let mut derived_key = self.derived_key.lock().map_err(lock_error)?;
+        let key = match derived_key.as_ref() {
+            Some((salt, key)) if *salt == file.salt => *key,
+            _ => {
+                let key = derive_key(&file.salt)?;
+                *derived_key = Some((file.salt.clone(), key));
+                key
+            },
+        };
+
+        let unbound_key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| crypto_error("Invalid key"))?;
+        Ok(LessSafeKey::new(unbound_key))
+    }
+}
+
+fn derive_key(salt: &str) -> Result<[u8; KEY_LEN], ArcError> {
+    let secret = match std::env::var(PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) => machine_id()?,
+    };
+    let salt = STANDARD.decode(salt).map_err(|_| crypto_error("Invalid salt"))?;
+
+    let mut key = [0u8; KEY_LEN];
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations must be non-zero");
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, secret.as_bytes(), &mut key);
+    Ok(key)
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>, ArcError> {
+        let _guard = self.lock.lock().map_err(lock_error)?;
+        let file = self.read()?;
+        let Some(entry) = file.entries.get(&entry_key(service, user)) else {
+            return Ok(None);
+        };
+
+        let nonce: [u8; NONCE_LEN] = STANDARD.decode(&entry.nonce).ok()
+            .and_then(|n| n.try_into().ok())
+            .ok_or_else(|| crypto_error("Invalid nonce"))?;
+        let mut in_out = STANDARD.decode(&entry.ciphertext).map_err(|_| crypto_error("Invalid ciphertext"))?;
+
+        // Decryption fails if the passphrase or machine ID changed since the credential was saved
+        let plaintext = self.key(&file)?
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(entry_key(service, user).as_bytes()), &mut in_out)
+            .map_err(|_| crypto_error("Unable to decrypt credentials, the passphrase or machine ID may have changed"))?;
+
+        Ok(Some(String::from_utf8_lossy(plaintext).into_owned()))
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<(), ArcError> {
+        let _guard = self.lock.lock().map_err(lock_error)?;
+        let mut file = self.read()?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| crypto_error("Unable to generate nonce"))?;
+
+        // Binding the entry key as additional data prevents ciphertexts from being swapped between entries
+        let key = entry_key(service, user);
+        let mut in_out = secret.as_bytes().to_vec();
+        self.key(&file)?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(key.as_bytes()), &mut in_out)
+            .map_err(|_| crypto_error("Unable to encrypt credentials"))?;
+
+        let entry = EncryptedEntry { nonce: STANDARD.encode(nonce), ciphertext: STANDARD.encode(in_out) };
+        file.entries.insert(key, entry);
+        self.write(&file)
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<bool, ArcError> {
+        let _guard = self.lock.lock().map_err(lock_error)?;
+        let mut file = self.read()?;
+        let deleted = file.entries.remove(&entry_key(service, user)).is_some();
+        if deleted {
+            self.write(&file)?;
+        }
+        Ok(deleted)
+    }
+}
+
+fn entry_key(service: &str, user: &str) -> String {
+    format!("{}/{}", service, user)
+}
+
+fn machine_id() -> Result<String, ArcError> {
+    MACHINE_ID_PATHS.iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| ArcError::invalid_config_error(format!(
+            "Unable to determine machine ID, please set {} to encrypt cached credentials",
+            PASSPHRASE_ENV_VAR,
+        )))
+}
+
+fn crypto_error(msg: &str) -> ArcError {
+    ArcError::CredentialStoreError(msg.to_string())
+}
+
+fn lock_error<T>(_: std::sync::PoisonError<T>) -> ArcError {
+    ArcError::CredentialStoreError("Credential store lock poisoned".to_string())
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::models::errors::ArcError;
+use crate::keyrings::credential_store::credential_store;
+
+const KEYRING_SERVICE: &str = "arcli-backend-github";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct GithubAppToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Client that wraps access to the configured credential store, for GitHub App installation tokens
+pub struct GithubKeyring {
+    service: String,
+    user: String,
+}
+
+impl GithubKeyring {
+    pub fn new(org: &str) -> Self {
+        Self {
+            service: KEYRING_SERVICE.to_string(),
+            user: org.to_string()
+        }
+    }
+
+    pub fn get_token(&self) -> Result<GithubAppToken, ArcError> {
+        let keyring_data = credential_store().get(&self.service, &self.user)?
+            .ok_or(keyring::Error::NoEntry)?;
+        Ok(serde_json::from_str::<GithubAppToken>(&keyring_data)?)
+    }
+
+    pub fn save_token(&self, token: &str, expires_at: DateTime<Utc>) -> Result<(), ArcError> {
+        let app_token = GithubAppToken { token: token.to_string(), expires_at };
+        let app_token_json = serde_json::to_string(&app_token)?;
+
+        credential_store().set(&self.service, &self.user, &app_token_json)
+    }
+}
@@ -1,8 +1,8 @@
 use chrono::{DateTime, Utc};
-use keyring::Entry;
 use serde::{Deserialize, Serialize};
 use crate::models::vault::VaultInstance;
 use crate::models::errors::ArcError;
+use crate::keyrings::credential_store::credential_store;
 
 const KEYRING_SERVICE: &str = "arcli-backend-vault";
 
@@ -13,7 +13,8 @@ pub(crate) struct VaultCredentials {
     pub renewable: bool,
 }
 
-/// Client that wraps access to operating system keyring's such as Keychain Access on MacOS
+/// Client that wraps access to the configured credential store, typically an operating system
+/// keyring such as Keychain Access on MacOS
 pub struct VaultKeyring {
     service: String,
     user: String,
@@ -28,8 +29,8 @@ impl VaultKeyring {
     }
 
     pub fn get_credentials(&self) -> Result<VaultCredentials, ArcError> {
-        let entry = Entry::new(&self.service, &self.user)?;
-        let keyring_data = entry.get_password()?;
+        let keyring_data = credential_store().get(&self.service, &self.user)?
+            .ok_or(keyring::Error::NoEntry)?;
         Ok(serde_json::from_str::<VaultCredentials>(&keyring_data)?)
     }
 
@@ -43,7 +44,11 @@ impl VaultKeyring {
         let credentials = VaultCredentials { client_token: client_token.to_string(), expires_at, renewable };
         let credentials_json = serde_json::to_string(&credentials)?;
 
-        let entry = Entry::new(&self.service, &self.user)?;
-        Ok(entry.set_password(&credentials_json)?)
+        credential_store().set(&self.service, &self.user, &credentials_json)
+    }
+
+    /// Removes any cached credentials, returning false if there were none
+    pub fn delete_credentials(&self) -> Result<bool, ArcError> {
+        credential_store().delete(&self.service, &self.user)
     }
 }
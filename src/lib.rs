@@ -12,7 +12,9 @@ use console::style;
 use models::errors::ArcError;
 use std;
 use models::config::CliConfig;
+use keyrings::credential_store::init_credential_store;
 use models::goals::Goal;
+use models::secret_display::{self, SecretDisplay};
 use models::state::State;
 use crate::tasks::TaskResult;
 
@@ -25,21 +27,24 @@ pub async fn run(args: CliArgs) -> Result<(), ArcError> {
     } else {
         CliConfig::default()
     };
+    init_credential_store(&config.credentials)?;
 
     let show_raw_output = args.raw_output;
+    let secret_display = args.secret_display();
 
     // A single ArcCommand may map to multiple goals
     // (e.g., Switch may require both AWS profile and Kube context selection)
     let terminal_goals = args.to_goals();
 
     // Execute each goal, including any dependent goals
-    execute_goals(terminal_goals, config, show_raw_output).await
+    execute_goals(terminal_goals, config, show_raw_output, secret_display).await
 }
 
 async fn execute_goals(
     terminal_goals: Vec<Goal>,
     config: CliConfig,
     show_raw_output: bool,
+    secret_display: SecretDisplay,
 ) -> Result<(), ArcError> {
     let mut goals = terminal_goals.clone();
     let mut eval_string = String::new();
@@ -89,6 +94,10 @@ async fn execute_goals(
                             outro_note(prompt, message)?;
                             value
                         },
+                        OutroText::Secret{ key, value, masked } => {
+                            print_secret_outro(&key, &value, &masked, secret_display).await?;
+                            value
+                        },
                         OutroText::None => String::new(),
                     };
 
@@ -128,6 +137,7 @@ pub enum GoalStatus {
 pub enum OutroText {
     SingleLine{ key: String, value: String },
     MultiLine{ key: String, value: String },
+    Secret{ key: String, value: String, masked: String },
     None,
 }
 
@@ -138,6 +148,41 @@ impl OutroText {
     pub fn multi(key: String, value: String) -> OutroText {
         OutroText::MultiLine { key, value }
     }
+    pub fn secret(key: String, value: String) -> OutroText {
+        let masked = secret_display::mask_value(&value);
+        OutroText::Secret { key, value, masked }
+    }
+    pub fn secret_fields(key: String, value: String) -> OutroText {
+        let masked = secret_display::mask_fields(&value);
+        OutroText::Secret { key, value, masked }
+    }
+}
+
+async fn print_secret_outro(
+    key: &str,
+    value: &str,
+    masked: &str,
+    display: SecretDisplay,
+) -> Result<(), ArcError> {
+    // Secrets are masked by default so that they don't linger in scrollback or get screen-shared
+    let key = match display {
+        SecretDisplay::Masked => key.to_string(),
+        SecretDisplay::Copy => {
+            secret_display::copy_to_clipboard(value)?;
+            format!("{} (copied to clipboard)", key)
+        },
+        SecretDisplay::Reveal(duration) => {
+            secret_display::reveal(key, value, duration).await?;
+            key.to_string()
+        },
+    };
+
+    if masked.contains('\n') {
+        outro_note(style(&key).green(), style(masked).dim())?;
+    } else {
+        outro(format!("{}: {}", style(&key).green(), style(masked).dim()))?;
+    }
+    Ok(())
 }
 
 fn config_dir() -> Result<std::path::PathBuf, ArcError> {
@@ -155,3 +200,31 @@ fn config_file() -> Result<std::path::PathBuf, ArcError> {
     path.push("config.toml");
     Ok(path)
 }
+
+/// Writes a file that only the current user can read. The contents are written to a temporary
+/// file first and then renamed over the original, so that a crash never leaves it half written.
+fn write_private_file(path: &std::path::Path, contents: &str) -> Result<(), ArcError> {
+    use std::io::Write;
+
+    let temp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    let _ = std::fs::remove_file(&temp_path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = options.open(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    Ok(result?)
+}
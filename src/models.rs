@@ -19,6 +19,7 @@ pub mod errors;
 pub mod aws_profile;
 pub mod kube_context;
 pub mod log_level;
+pub mod secret_display;
 
 pub(crate) async fn get_env_configs() -> Result<EnvConfigSections, ArcError> {
     // Use real filesystem and environment access
@@ -3,11 +3,18 @@ use console::style;
 use serde::Deserialize;
 use unicode_width::UnicodeWidthStr;
 use crate::models::aws_profile::{AwsAccount, AwsProfileInfo};
+use crate::models::config::{ArgoImageRule, ArgoResourceIdentity};
+use crate::models::github::GithubPr;
 
 const ARGO_DEV_NAME: &str = "dev";
 const ARGO_STAGE_NAME: &str = "stage";
 const ARGO_PROD_NAME: &str = "prod";
 const SYNCED: &str = "Synced";
+const HEALTHY: &str = "Healthy";
+const PROGRESSING: &str = "Progressing";
+const DEGRADED: &str = "Degraded";
+const REFRESH_ANNOTATION: &str = "argocd.argoproj.io/refresh";
+const DEFAULT_IMAGE_PATH: &str = "spec.template.spec.containers.0.image";
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum ArgoCdInstance {
@@ -46,6 +53,27 @@ impl ArgoCdInstance {
     }
 }
 
+/// An operation that changes the state of an ArgoCD application
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ArgoOperation {
+    Sync { prune: bool, dry_run: bool },
+    Refresh { hard: bool },
+    /// Roll back to the given history ID, or prompt for one if None
+    Rollback(Option<u64>),
+}
+
+impl ArgoOperation {
+    pub fn name(&self) -> &str {
+        match self {
+            ArgoOperation::Sync { dry_run: true, .. } => "Sync (dry run)",
+            ArgoOperation::Sync { .. } => "Sync",
+            ArgoOperation::Refresh { hard: true } => "Hard refresh",
+            ArgoOperation::Refresh { .. } => "Refresh",
+            ArgoOperation::Rollback(_) => "Rollback",
+        }
+    }
+}
+
 impl From<&str> for ArgoCdInstance {
     fn from(name: &str) -> Self {
         match name {
@@ -76,12 +104,90 @@ pub(crate) struct ArgoApplicationList {
 #[derive(Deserialize, Debug)]
 pub(crate) struct ArgoApplication {
     pub(crate) metadata: ArgoMetadata,
+    spec: Option<ArgoApplicationSpec>,
     pub(crate) status: ArgoStatus,
 }
 
+impl ArgoApplication {
+    /// The ArgoCD project that the application belongs to, which is also its chart directory in gitops
+    pub(crate) fn project(&self) -> Option<&str> {
+        self.spec.as_ref().map(|spec| spec.project.as_str())
+    }
+
+    /// Path in gitops of the version file that the application's Helm values are read from, which
+    /// identifies its region when an environment is deployed to several,
+    /// e.g. "charts/arc/web-bff/envs/models/us-west-2/prod/version.yaml"
+    pub(crate) fn version_file(&self) -> Option<String> {
+        let spec = self.spec.as_ref()?;
+        spec.source.iter().chain(spec.sources.iter())
+            .find_map(|source| {
+                let path = source.path.as_deref()?.trim_end_matches('/');
+                let value_file = source.helm.as_ref()?.value_files.iter()
+                    .find(|file| file.ends_with("version.yaml") && !file.contains(".."))?;
+                Some(format!("{}/{}", path, value_file.trim_start_matches("./")))
+            })
+    }
+
+    pub(crate) fn sync_status(&self) -> &str {
+        &self.status.sync.status
+    }
+
+    pub(crate) fn health(&self) -> Option<&ArgoHealthStatus> {
+        self.status.health.as_ref()
+    }
+
+    /// Phase of the current or most recent operation, e.g. "Running" or "Succeeded"
+    pub(crate) fn operation_phase(&self) -> Option<&str> {
+        self.status.operation_state.as_ref().and_then(|op_state| op_state.phase.as_deref())
+    }
+
+    pub(crate) fn operation_message(&self) -> Option<&str> {
+        self.status.operation_state.as_ref().and_then(|op_state| op_state.message.as_deref())
+    }
+
+    /// Start time of the current or most recent operation, which identifies it across polls
+    pub(crate) fn operation_started_at(&self) -> Option<&str> {
+        self.status.operation_state.as_ref().and_then(|op_state| op_state.started_at.as_deref())
+    }
+
+    /// ArgoCD removes the refresh annotation once it has finished comparing the app against git
+    pub(crate) fn is_refresh_pending(&self) -> bool {
+        self.metadata.annotations.contains_key(REFRESH_ANNOTATION)
+    }
+
+    /// Deployment history, most recent first
+    pub(crate) fn history(&self) -> Vec<&ArgoRevisionHistory> {
+        let mut history: Vec<&ArgoRevisionHistory> = self.status.history.iter().collect();
+        history.sort_by_key(|h| std::cmp::Reverse(h.id));
+        history
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ArgoApplicationSpec {
+    project: String,
+    source: Option<ArgoApplicationSource>,
+    #[serde(default)]
+    sources: Vec<ArgoApplicationSource>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArgoApplicationSource {
+    path: Option<String>,
+    helm: Option<ArgoHelmSource>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArgoHelmSource {
+    #[serde(rename = "valueFiles", default)]
+    value_files: Vec<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct ArgoMetadata {
     pub(crate) name: String,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -90,6 +196,23 @@ pub(crate) struct ArgoStatus {
     #[serde(rename = "operationState")]
     operation_state: Option<ArgoOperationState>,
     summary: Option<ArgoSummary>,
+    #[serde(default)]
+    history: Vec<ArgoRevisionHistory>,
+    health: Option<ArgoHealthStatus>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ArgoHealthStatus {
+    pub(crate) status: String,
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ArgoRevisionHistory {
+    pub(crate) id: u64,
+    pub(crate) revision: Option<String>,
+    #[serde(rename = "deployedAt")]
+    pub(crate) deployed_at: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -104,6 +227,10 @@ struct ArgoSyncStatus {
 
 #[derive(Deserialize, Debug)]
 struct ArgoOperationState {
+    phase: Option<String>,
+    message: Option<String>,
+    #[serde(rename = "startedAt")]
+    started_at: Option<String>,
     #[serde(rename = "finishedAt")]
     finished_at: Option<String>,
     #[serde(rename = "syncResult")]
@@ -128,6 +255,11 @@ struct ArgoResource {
 pub struct AppInfo {
     pub(crate) name: String,
     pub(crate) sync_status: String,
+    pub(crate) health_status: String,
+    pub(crate) health_message: Option<String>,
+    pub(crate) operation_phase: Option<String>,
+    pub(crate) operation_message: Option<String>,
+    pub(crate) started_at: Option<String>,
     pub(crate) finished_at: Option<String>,
     pub(crate) image_tag: String,
 }
@@ -135,50 +267,73 @@ pub struct AppInfo {
 impl AppInfo {
     pub(crate) fn with_image_tag(self, image_tag: &str) -> AppInfo {
         AppInfo {
-            name: self.name,
-            sync_status: self.sync_status,
-            finished_at: self.finished_at,
             image_tag: image_tag.to_string(),
+            ..self
         }
     }
 }
 
-impl From<ArgoApplication> for AppInfo {
-    fn from(argo_app: ArgoApplication) -> Self {
-        // Lookup the resource identifiers for this app
-        let app_name = &argo_app.metadata.name;
-        let (group, kind, name) = k8_resource_identity(app_name);
+impl AppInfo {
+    /// Summarises an application, taking its version from the image of the given resource
+    pub(crate) fn new(argo_app: ArgoApplication, resource: &ArgoResourceIdentity) -> Self {
+        let ArgoResourceIdentity { group, kind, name } = resource;
 
         // Attempt to find the image tag in the resources section of the response
         // Fallback to searching the list of images in the summary section of the response
         let image_tag = extract_resource_image_tag(&argo_app, group, kind, name)
             .unwrap_or_else(|| {
                 let image_map = extract_summary_image_tags(&argo_app);
-                image_map.get(name)
+                image_map.get(name.as_str())
                     .map(|tag| tag.to_string())
                     .unwrap_or_else(|| "unknown".to_string())
             });
 
+        let (health_status, health_message) = match argo_app.status.health {
+            Some(health) => (health.status, health.message),
+            None => ("Unknown".to_string(), None),
+        };
+        let (operation_phase, operation_message, started_at, finished_at) = match argo_app.status.operation_state {
+            Some(op_state) => (op_state.phase, op_state.message, op_state.started_at, op_state.finished_at),
+            None => (None, None, None, None),
+        };
+
         AppInfo {
             name: argo_app.metadata.name,
             sync_status: argo_app.status.sync.status,
-            finished_at: argo_app.status.operation_state
-                .and_then(|op_state| op_state.finished_at),
+            health_status,
+            health_message,
+            operation_phase,
+            operation_message,
+            started_at,
+            finished_at,
             image_tag,
         }
     }
 }
 
-fn k8_resource_identity(app_name: &str) -> (&str, &str, &str) {
-    // Technically a resource is uniquely identified by (Group + Kind + namespace + name + version)
-    // but for our purposes I think that we can get away with just using the (Group + Kind + name)
-    match app_name {
-        "apollo-server" => ("apps", "Deployment", "agility-graph"),
-        "database-migration" => ("batch", "Job", "flyway"),
-        "web-bff" => ("apps", "Deployment", "backend"),
-        "webhook-integ" => ("apps", "Deployment", "webhook-integration"),
-        _ => ("apps", "Deployment", app_name)
-    }
+/// Finds the image tag of an app's main resource within the manifests rendered for a revision
+pub(crate) fn extract_manifest_image_tag(
+    resource: &ArgoResourceIdentity,
+    image_rule: Option<&ArgoImageRule>,
+    manifests: &[String],
+) -> Option<String> {
+    let image_path = image_rule.map(|rule| rule.image_path.as_str()).unwrap_or(DEFAULT_IMAGE_PATH);
+    manifests.iter()
+        .filter_map(|manifest| serde_json::from_str::<serde_json::Value>(manifest).ok())
+        .find(|m| m["kind"] == resource.kind.as_str() && m["metadata"]["name"] == resource.name.as_str())
+        .and_then(|m| extract_image_tag(&m, image_path))
+}
+
+/// Follows a dot separated path through a manifest to an image and returns its tag, where numeric
+/// segments of the path index into lists
+pub(crate) fn extract_image_tag(manifest: &serde_json::Value, image_path: &str) -> Option<String> {
+    let image = image_path.split('.')
+        .try_fold(manifest, |value, segment| match segment.parse::<usize>() {
+            Ok(index) => value.get(index),
+            Err(_) => value.get(segment),
+        })?
+        .as_str()?;
+    image.rsplit_once(':').map(|(_, tag)| tag.to_string())
 }
 
 fn extract_resource_image_tag(argo_app: &ArgoApplication, group: &str, kind: &str, repo_name: &str) -> Option<String> {
@@ -214,7 +369,7 @@ fn extract_summary_image_tags(argo_app: &ArgoApplication) -> HashMap<&str, &str>
 
 impl AppInfo {
     pub(crate) fn header() -> String {
-        format!("{:<30} {:<8} {:<23} {:<40}", "Application", "Status", "Last Synced", "Version")
+        format!("{:<30} {:<8} {:<12} {:<23} {:<40}", "Application", "Status", "Health", "Last Synced", "Version")
     }
 
     pub(crate) fn minimal_text(&self, is_version_updated: bool) -> String {
@@ -233,92 +388,271 @@ impl AppInfo {
     pub(crate) fn is_synced(&self) -> bool {
         self.sync_status == SYNCED
     }
+
+    /// Describes why the app's most recent sync failed or why it's degraded, if either is the case
+    pub(crate) fn failure(&self) -> Option<String> {
+        if let Some(phase @ ("Failed" | "Error")) = self.operation_phase.as_deref() {
+            let message = self.operation_message.as_deref().unwrap_or("no details");
+            return Some(format!("sync {}: {}", phase.to_lowercase(), message));
+        }
+
+        if self.health_status == DEGRADED {
+            let message = self.health_message.as_deref().unwrap_or("no details");
+            return Some(format!("degraded: {}", message));
+        }
+        None
+    }
 }
 
-impl std::fmt::Display for AppInfo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let emoji = if self.sync_status == SYNCED { "✅" } else { "❌" };
+/// A field of [AppInfo] that is tracked for changes while watching apps
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum AppField {
+    Sync,
+    Health,
+    Image,
+}
+
+impl AppInfo {
+    /// Describes each tracked field that differs from a previous snapshot of the same app
+    pub(crate) fn changes_since(&self, previous: &AppInfo) -> Vec<(AppField, String)> {
+        let mut changes = Vec::new();
+        if self.sync_status != previous.sync_status {
+            changes.push((AppField::Sync, format!("sync {} → {}", previous.sync_status, self.sync_status)));
+        }
+        if self.health_status != previous.health_status {
+            changes.push((AppField::Health, format!("health {} → {}", previous.health_status, self.health_status)));
+        }
+        if self.image_tag != previous.image_tag {
+            changes.push((AppField::Image, format!("image {} → {}", previous.image_tag, self.image_tag)));
+        }
+        changes
+    }
+
+    /// Table row with the given fields highlighted. The app name is highlighted for sync changes,
+    /// since the sync status is only shown as an emoji.
+    pub(crate) fn highlighted_row(&self, highlighted: &[AppField]) -> String {
+        // Pad before styling, otherwise the escape codes would count towards the column width
+        let highlight = |field: AppField, text: String| {
+            if highlighted.contains(&field) {
+                style(text).yellow().bold().to_string()
+            } else {
+                text
+            }
+        };
+
+        // An app is only fine once it's both synced and healthy, e.g. a synced app can still be crash looping
+        let emoji = match (self.is_synced(), self.health_status.as_str()) {
+            (true, HEALTHY) => "✅",
+            (true, PROGRESSING) => "⏳",
+            _ => "❌",
+        };
         let emoji_width = emoji.width();
         let emoji_padding = " ".repeat(8_usize.saturating_sub(emoji_width));
-        write!(
-            f,
-            "{:<30} {}{} {:<23} {:<40}",
-            self.name,
+        format!(
+            "{} {}{} {} {:<23} {}",
+            highlight(AppField::Sync, format!("{:<30}", self.name)),
             emoji,
             emoji_padding,
+            highlight(AppField::Health, format!("{:<12}", self.health_status)),
             self.finished_at.as_deref().unwrap_or("unknown"),
-            self.image_tag
+            highlight(AppField::Image, format!("{:<40}", self.image_tag)),
         )
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct ArgoSensorResource {
-    pub(crate) manifest: String,
+impl std::fmt::Display for AppInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.highlighted_row(&[]))
+    }
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct SensorManifest {
-    pub(crate) spec: SensorSpec,
-}
-
-impl SensorManifest {
-    pub(crate) fn image_tag(&self) -> String {
-        self.spec.triggers.first()
-            .and_then(|t| t.template.k8s.as_ref())
-            .map(|k| &k.source.resource.spec.templates)
-            .and_then(|templates| templates.first())
-            .and_then(|wt| wt.container.as_ref())
-            .and_then(|c| c.image.split(':').last().map(ToString::to_string))
-            .unwrap_or_else(|| "unknown".to_string())
+/// Image tags of an app in each ArgoCD instance, in the order of [ArgoCdInstance::all]
+#[derive(Debug)]
+pub struct AppVersions {
+    pub(crate) name: String,
+    pub(crate) image_tags: Vec<Option<String>>,
+}
+
+impl AppVersions {
+    pub(crate) fn header() -> String {
+        let instances: Vec<String> = ArgoCdInstance::all().iter()
+            .map(|instance| format!("{:<30}", instance.name()))
+            .collect();
+        format!("{:<30} {}", "Application", instances.join(" "))
+    }
+
+    /// Whether every instance before the last one runs a different image than it, e.g. dev and stage
+    /// both differ from prod. Tags aren't ordered, so this doesn't mean that they're newer than prod.
+    pub(crate) fn differs_from_last(&self) -> bool {
+        let Some((last, earlier)) = self.image_tags.split_last() else {
+            return false;
+        };
+        !earlier.is_empty() && earlier.iter().all(|tag| tag.is_some() && tag != last)
+    }
+
+    /// Table row where each tag that differs from the previous instance's is marked, since that's
+    /// where a version is waiting to be promoted
+    pub(crate) fn row(&self) -> String {
+        let cells: Vec<String> = self.image_tags.iter().enumerate()
+            .map(|(i, tag)| {
+                let text = tag.as_deref().unwrap_or("-");
+                let differs = i > 0 && tag.is_some() && self.image_tags[i - 1] != *tag;
+                if differs {
+                    // Pad before styling, otherwise the escape codes would count towards the column width
+                    style(format!("{:<30}", format!("{} *", text))).yellow().to_string()
+                } else {
+                    format!("{:<30}", text)
+                }
+            })
+            .collect();
+        format!("{:<30} {}", self.name, cells.join(" "))
     }
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct SensorSpec {
-    #[serde(default)]
-    pub(crate) triggers: Vec<SensorTrigger>,
+/// A deployment from an application's history, along with the gitops change that it rolled out
+#[derive(Debug)]
+pub struct ArgoDeployment {
+    pub(crate) id: u64,
+    pub(crate) deployed_at: Option<String>,
+    pub(crate) revision: Option<String>,
+    pub(crate) version_bump: Option<VersionBump>,
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct SensorTrigger {
-    pub(crate) template: SensorTriggerTemplate,
+/// The most recent commit, as of a deployed revision, that changed an app's image tag
+#[derive(Debug)]
+pub struct VersionBump {
+    pub(crate) commit: String,
+    pub(crate) previous_tag: Option<String>,
+    pub(crate) tag: String,
+    pub(crate) pull_request: Option<GithubPr>,
 }
 
 #[derive(Deserialize, Debug)]
-pub(crate) struct SensorTriggerTemplate {
-    pub(crate) k8s: Option<K8sTrigger>,
+pub(crate) struct ArgoResourceTree {
+    #[serde(default)]
+    pub(crate) nodes: Vec<ArgoResourceNode>,
 }
 
 #[derive(Deserialize, Debug)]
-pub(crate) struct K8sTrigger {
-    pub(crate) source: K8sSource,
+pub(crate) struct ArgoResourceNode {
+    #[serde(default)]
+    pub(crate) group: String,
+    pub(crate) version: String,
+    pub(crate) kind: String,
+    #[serde(default)]
+    pub(crate) namespace: String,
+    pub(crate) name: String,
+    pub(crate) health: Option<ArgoHealthStatus>,
+    #[serde(default)]
+    pub(crate) images: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct K8sSource {
-    pub(crate) resource: WorkflowWorkerResource,
+impl ArgoResourceNode {
+    /// Resources that run the app's workloads, as opposed to supporting resources such as ConfigMaps
+    pub(crate) fn is_workload(&self) -> bool {
+        matches!(self.kind.as_str(), "Deployment" | "Job" | "Sensor")
+    }
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct WorkflowWorkerResource {
-    pub(crate) spec: WorkflowWorkerSpec,
+/// Health and status of a single workload within an ArgoCD application
+#[derive(Debug)]
+pub struct ArgoResourceDetail {
+    pub(crate) kind: String,
+    pub(crate) name: String,
+    pub(crate) health: String,
+    pub(crate) health_message: Option<String>,
+    pub(crate) ready: Option<String>,
+    pub(crate) images: Vec<String>,
+    pub(crate) conditions: Vec<String>,
+}
+
+impl ArgoResourceDetail {
+    /// Combines a resource tree node with its live manifest, if it could be fetched
+    pub(crate) fn new(node: &ArgoResourceNode, manifest: Option<&serde_json::Value>) -> Self {
+        let health = node.health.as_ref();
+        let mut detail = ArgoResourceDetail {
+            kind: node.kind.clone(),
+            name: node.name.clone(),
+            health: health.map(|h| h.status.clone()).unwrap_or_else(|| "Unknown".to_string()),
+            health_message: health.and_then(|h| h.message.clone()),
+            ready: None,
+            images: node.images.clone(),
+            conditions: Vec::new(),
+        };
+
+        let Some(manifest) = manifest else {
+            return detail;
+        };
+
+        let spec = &manifest["spec"];
+        let status = &manifest["status"];
+        let (ready, images) = match node.kind.as_str() {
+            "Deployment" => {
+                let ready = format!("{}/{} ready", status["readyReplicas"].as_u64().unwrap_or(0), spec["replicas"].as_u64().unwrap_or(1));
+                (Some(ready), container_images(&spec["template"]["spec"]["containers"]))
+            },
+            "Job" => {
+                let ready = format!("{}/{} succeeded", status["succeeded"].as_u64().unwrap_or(0), spec["completions"].as_u64().unwrap_or(1));
+                (Some(ready), container_images(&spec["template"]["spec"]["containers"]))
+            },
+            _ => {
+                // Sensors launch workflows, whose containers are nested within each trigger
+                let images = spec["triggers"].as_array().into_iter().flatten()
+                    .flat_map(|trigger| trigger["template"]["k8s"]["source"]["resource"]["spec"]["templates"].as_array().into_iter().flatten())
+                    .filter_map(|template| template["container"]["image"].as_str().map(String::from))
+                    .collect();
+                (None, images)
+            },
+        };
+        detail.ready = ready;
+        if !images.is_empty() {
+            detail.images = images;
+        }
+        detail.conditions = error_conditions(&status["conditions"]);
+        detail
+    }
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct WorkflowWorkerSpec {
-    #[serde(default)]
-    pub(crate) templates: Vec<WorkflowWorkerTemplate>,
+fn container_images(containers: &serde_json::Value) -> Vec<String> {
+    containers.as_array().into_iter().flatten()
+        .filter_map(|container| container["image"].as_str().map(String::from))
+        .collect()
+}
+
+/// Formats the conditions that indicate a problem, most recent first. Most condition types describe
+/// something good (e.g. Available), but a few describe a failure when true (e.g. ReplicaFailure).
+fn error_conditions(conditions: &serde_json::Value) -> Vec<String> {
+    const MAX_CONDITIONS: usize = 3;
+
+    let mut errors: Vec<&serde_json::Value> = conditions.as_array().into_iter().flatten()
+        .filter(|condition| match condition["type"].as_str() {
+            Some("Failed" | "ReplicaFailure") => condition["status"] == "True",
+            _ => condition["status"] == "False",
+        })
+        .collect();
+    errors.sort_by_key(|condition| std::cmp::Reverse(condition["lastTransitionTime"].as_str().unwrap_or_default().to_string()));
+
+    errors.into_iter()
+        .take(MAX_CONDITIONS)
+        .map(|condition| format!(
+            "{} ({}): {} [{}]",
+            condition["type"].as_str().unwrap_or("Unknown"),
+            condition["reason"].as_str().unwrap_or("no reason"),
+            condition["message"].as_str().unwrap_or("no message"),
+            condition["lastTransitionTime"].as_str().unwrap_or("unknown"),
+        ))
+        .collect()
 }
 
 #[derive(Deserialize, Debug)]
-pub(crate) struct WorkflowWorkerTemplate {
-    pub(crate) container: Option<Container>,
+pub(crate) struct ArgoResourceManifest {
+    pub(crate) manifest: String,
 }
 
 #[derive(Deserialize, Debug)]
-pub(crate) struct Container {
-    pub(crate) image: String,
+pub(crate) struct ArgoManifestResponse {
+    #[serde(default)]
+    pub(crate) manifests: Vec<String>,
 }
 
 #[derive(Deserialize, Debug)]
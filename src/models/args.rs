@@ -1,10 +1,16 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 use std;
 use std::convert::From;
 use std::path::PathBuf;
+use std::time::Duration;
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use crate::models::argo::ArgoOperation;
+use crate::models::github::PrLocator;
 use crate::models::goals::Goal;
 use crate::models::log_level::LogLevel;
+use crate::models::rds::DbRole;
+use crate::models::secret_display::SecretDisplay;
+use crate::models::vault::VaultWrite;
 
 // This constant must be kept in sync with its usage in the #[arg] attributes below
 pub const PROMPT: &str = "PROMPT";
@@ -21,22 +27,96 @@ pub struct CliArgs {
     )]
     pub(crate) raw_output: bool,
 
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "reveal",
+        help = "Copy secret values to the clipboard (via OSC 52) instead of displaying them"
+    )]
+    pub(crate) copy: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "10",
+        help = "Temporarily display secret values, clearing them after SECONDS, e.g. '--reveal=30' (defaults to 10)"
+    )]
+    // Will be 10 if the user included the flag without a value, None if they didn't include the flag at all
+    pub(crate) reveal: Option<u64>,
+
     #[command(subcommand)]
     pub(crate) command: CliCommand,
 }
 
 impl CliArgs {
+    pub(crate) fn secret_display(&self) -> SecretDisplay {
+        match (self.copy, self.reveal) {
+            (true, _) => SecretDisplay::Copy,
+            (false, Some(seconds)) => SecretDisplay::Reveal(std::time::Duration::from_secs(seconds)),
+            (false, None) => SecretDisplay::Masked,
+        }
+    }
+
     pub(crate) fn to_goals(self) -> Vec<Goal> {
         match self.command {
-            CliCommand::Argo { pull_request} => vec![
-                Goal::terminal_argo(pull_request)
-            ],
+            CliCommand::Argo { pull_request, branch, commit, here, project, timeout, watch, interval, action: None } => {
+                let pr_locator = match (pull_request, branch, commit, here) {
+                    // We use a sentinel value of zero when user specifies '-p' option without a value
+                    (Some(0), _, _, _) => Some(PrLocator::RecentlyOpened(Duration::from_mins(10))),
+                    (Some(pr), _, _, _) => Some(PrLocator::Number(pr)),
+                    (_, Some(branch), _, _) => Some(PrLocator::Branch(branch)),
+                    (_, _, Some(commit), _) => Some(PrLocator::Commit(commit)),
+                    (_, _, _, true) => Some(PrLocator::CurrentBranch),
+                    _ => None,
+                };
+                vec![Goal::terminal_argo(pr_locator, project, timeout, watch.then_some(interval))]
+            },
+            CliCommand::Argo { action: Some(action), .. } => {
+                match action {
+                    ArgoAction::App { name, history: true, env } => vec![
+                        Goal::terminal_argo_app_history_known(name, env)
+                    ],
+                    ArgoAction::App { name, history: false, env } => vec![
+                        Goal::terminal_argo_app_details_known(name, env)
+                    ],
+                    ArgoAction::Matrix { promotable, project } => vec![
+                        Goal::terminal_argo_version_matrix_known(promotable, project)
+                    ],
+                    ArgoAction::Sync { app, prune, dry_run, timeout, env } => vec![
+                        Goal::terminal_argo_operation_completed(app, ArgoOperation::Sync { prune, dry_run }, timeout, env)
+                    ],
+                    ArgoAction::Refresh { app, hard, timeout, env } => vec![
+                        Goal::terminal_argo_operation_completed(app, ArgoOperation::Refresh { hard }, timeout, env)
+                    ],
+                    ArgoAction::Rollback { app, to, timeout, env } => vec![
+                        Goal::terminal_argo_operation_completed(app, ArgoOperation::Rollback(to), timeout, env)
+                    ],
+                }
+            },
+            CliCommand::Auth { action } => {
+                match action {
+                    AuthAction::Status => vec![Goal::terminal_auth_status_known()],
+                    AuthAction::Logout { argo, vault, sso, all } => vec![
+                        Goal::terminal_credentials_cleared(argo, vault, sso, all)
+                    ],
+                }
+            },
             CliCommand::Bazel { action } => {
                 match action {
                     BazelAction::Run { target } => vec![Goal::terminal_bazel_target_running(target)],
                 }
             },
             CliCommand::Completions => vec![Goal::terminal_tab_completions()],
+            CliCommand::Gh { action } => {
+                match action {
+                    GhAction::Runs { repo, branch, workflow, wait } => vec![
+                        Goal::terminal_github_runs_known(repo, branch, workflow, wait)
+                    ],
+                }
+            },
             CliCommand::Influx { action } => {
                 match action {
                     InfluxAction::Ui { aws_profile } => vec![
@@ -57,18 +137,57 @@ impl CliArgs {
                     ],
                 }
             },
-            CliCommand::Pgcli { aws_profile } => vec![Goal::terminal_pgcli_running(aws_profile)],
+            CliCommand::Pgcli { aws_profile, role } => vec![Goal::terminal_pgcli_running(aws_profile, role)],
             CliCommand::PortForward { namespace, service, port, group, kube_context } => vec![
                 Goal::terminal_port_forward_established(namespace, service, port, group, kube_context)
             ],
+            CliCommand::Promote { apps, from, to, branch, watch } => {
+                let branch = branch.unwrap_or_else(|| format!("promote/{}/{}", to, Utc::now().format("%Y%m%d-%H%M%S")));
+                let promote_goal = Goal::terminal_apps_promoted(apps, from, to, branch.clone());
+
+                // Goals are completed last first, so the PR is opened before its apps are watched
+                if watch {
+                    vec![Goal::terminal_argo(Some(PrLocator::Branch(branch)), None, None, None), promote_goal]
+                } else {
+                    vec![promote_goal]
+                }
+            },
             CliCommand::Secret { store } => {
                 match store {
                     SecretStore::Aws { name, aws_profile } => vec![
                         Goal::terminal_aws_secret_known(name, aws_profile)
                     ],
-                    SecretStore::Vault { path, field, aws_profile } => vec![
+                    SecretStore::Vault { path, field, aws_profile, action: None } => vec![
                         Goal::terminal_vault_secret_known(path, field, aws_profile)
                     ],
+                    SecretStore::Vault { action: Some(action), .. } => {
+                        match action {
+                            VaultAction::Set { path, fields, aws_profile } => vec![
+                                Goal::terminal_vault_secret_written(path, VaultWrite::Set(fields), aws_profile)
+                            ],
+                            VaultAction::Patch { path, fields, aws_profile } => vec![
+                                Goal::terminal_vault_secret_written(path, VaultWrite::Patch(fields), aws_profile)
+                            ],
+                            VaultAction::Delete { path, aws_profile } => vec![
+                                Goal::terminal_vault_secret_written(path, VaultWrite::Delete, aws_profile)
+                            ],
+                            VaultAction::History { path, aws_profile } => vec![
+                                Goal::terminal_vault_secret_history_known(path, aws_profile)
+                            ],
+                            VaultAction::Diff { path, from, to, aws_profile } => vec![
+                                Goal::terminal_vault_secret_diff_known(path, from, to, aws_profile)
+                            ],
+                            VaultAction::Rollback { path, to, aws_profile } => vec![
+                                Goal::terminal_vault_secret_written(path, VaultWrite::Rollback(to), aws_profile)
+                            ],
+                            VaultAction::Ls { prefix, recursive, aws_profile } => vec![
+                                Goal::terminal_vault_secret_paths_known(prefix, recursive, aws_profile)
+                            ],
+                            VaultAction::Find { pattern, prefix, aws_profile } => vec![
+                                Goal::terminal_vault_secret_matches_known(pattern, prefix, aws_profile)
+                            ],
+                        }
+                    },
                 }
             },
             CliCommand::Switch { aws_profile, kube_context } => {
@@ -96,7 +215,8 @@ impl CliArgs {
 
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum CliCommand {
-    #[command(about = "Monitor ArgoCD application statuses")]
+    #[command(about = "Monitor ArgoCD application statuses", args_conflicts_with_subcommands = true)]
+    #[command(group(ArgGroup::new("pr").args(["pull_request", "branch", "commit", "here"])))]
     Argo {
         #[arg(
             short, long,
@@ -106,6 +226,35 @@ pub enum CliCommand {
         )]
         // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
         pull_request: Option<u32>,
+
+        #[arg(short, long, help = "Branch of the services-gitops PR used to infer which apps to monitor")]
+        branch: Option<String>,
+
+        #[arg(long, help = "Commit SHA of the services-gitops PR used to infer which apps to monitor")]
+        commit: Option<String>,
+
+        #[arg(long, help = "Use the PR for the current branch of the services-gitops checkout in this directory")]
+        here: bool,
+
+        #[arg(long, help = "ArgoCD project whose apps are shown (if omitted, uses all configured projects)")]
+        project: Option<String>,
+
+        #[arg(long, requires = "pr", value_parser = parse_duration, help = "Give up waiting for the PR's apps to sync after this long, e.g. '15m'")]
+        timeout: Option<Duration>,
+
+        #[arg(short, long, conflicts_with = "pr", help = "Keep a live table of all apps, highlighting changes as they happen")]
+        watch: bool,
+
+        #[arg(long, requires = "watch", default_value = "5s", value_parser = parse_duration, help = "How often to refresh the watched apps, e.g. '10s' or '1m'")]
+        interval: Duration,
+
+        #[command(subcommand)]
+        action: Option<ArgoAction>,
+    },
+    #[command(about = "Show or clear cached credentials for ArgoCD, Vault and AWS SSO")]
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
     },
     #[command(about = "Run a Bazel command")]
     Bazel {
@@ -114,6 +263,11 @@ pub enum CliCommand {
     },
     #[command(about = "Generate a shell completion script")]
     Completions,
+    #[command(about = "Interact with GitHub")]
+    Gh {
+        #[command(subcommand)]
+        action: GhAction,
+    },
     #[command(about = "Interact with InfluxDB")]
     Influx {
         #[command(subcommand)]
@@ -129,6 +283,9 @@ pub enum CliCommand {
         #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
         // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
         aws_profile: Option<String>,
+
+        #[arg(long, help = "Connect with short-lived Vault credentials for this role (defaults to the RDS master secret)")]
+        role: Option<DbRole>,
     },
     #[command(about = "Start port-forwarding to one or more Kubernetes service(s)")]
     PortForward {
@@ -155,6 +312,23 @@ pub enum CliCommand {
         // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
         kube_context: Option<String>,
     },
+    #[command(about = "Open a services-gitops PR that promotes apps to the versions deployed in another environment")]
+    Promote {
+        #[arg(required = true, help = "Names of the ArgoCD applications to promote, e.g. 'web-bff'")]
+        apps: Vec<String>,
+
+        #[arg(long, default_value = "stage", value_parser = ["dev", "stage", "prod"], help = "ArgoCD instance whose deployed versions are promoted")]
+        from: String,
+
+        #[arg(long, default_value = "prod", value_parser = ["dev", "stage", "prod"], help = "Environment whose version files are updated")]
+        to: String,
+
+        #[arg(short, long, help = "Name of the branch to create (defaults to promote/<to>/<timestamp>)")]
+        branch: Option<String>,
+
+        #[arg(short, long, help = "Wait for the promoted apps to sync once the PR is merged")]
+        watch: bool,
+    },
     #[command(about = "Retrieve a secret value from AWS Secrets Manager or Vault")]
     Secret {
         #[command(subcommand)]
@@ -183,7 +357,7 @@ pub enum SecretStore {
         // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
         aws_profile: Option<String>,
     },
-    #[command(about = "Retrieve a secret from Vault")]
+    #[command(about = "Retrieve a secret from Vault", args_conflicts_with_subcommands = true)]
     Vault {
         #[arg(short, long, help = "Path to secret to retrieve (if omitted, will prompt)")]
         path: Option<String>,
@@ -194,6 +368,120 @@ pub enum SecretStore {
         #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
         // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
         aws_profile: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<VaultAction>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VaultAction {
+    #[command(about = "Replace all fields of a secret, creating it if it doesn't exist")]
+    Set {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(
+            required = true,
+            value_parser = parse_secret_field,
+            help = "Fields to write as KEY=VALUE (use KEY alone to be prompted for a hidden value)"
+        )]
+        fields: Vec<(String, Option<String>)>,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "Add or update fields of an existing secret, leaving other fields untouched")]
+    Patch {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(
+            required = true,
+            value_parser = parse_secret_field,
+            help = "Fields to write as KEY=VALUE (use KEY alone to be prompted for a hidden value)"
+        )]
+        fields: Vec<(String, Option<String>)>,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "Soft-delete the latest version of a secret")]
+    Delete {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "List all versions of a secret")]
+    History {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "Show which fields changed between two versions of a secret (values are masked)")]
+    Diff {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(long, help = "Version to compare from")]
+        from: u64,
+
+        #[arg(long, help = "Version to compare to (defaults to the latest version)")]
+        to: Option<u64>,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "List secret paths, optionally including all nested paths")]
+    Ls {
+        #[arg(help = "Path prefix to list, e.g. 'mp/' (defaults to the root of the namespace)")]
+        prefix: Option<String>,
+
+        // -r is already taken by the global --raw-output flag
+        #[arg(short = 'R', long, help = "List all nested paths as a tree")]
+        recursive: bool,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(
+        about = "Find secrets whose path or field names contain a pattern (case-insensitive)",
+        long_about = "Find secrets whose path or field names contain a pattern (case-insensitive).\n\n\
+            Vault can't list a secret's field names on their own, so this reads every secret beneath the prefix. \
+            Secrets that your policies allow listing but not reading are skipped."
+    )]
+    Find {
+        #[arg(help = "Text to search for, e.g. 'postgres'")]
+        pattern: String,
+
+        #[arg(long, help = "Only search beneath this path prefix, e.g. 'mp/'")]
+        prefix: Option<String>,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
+    },
+    #[command(about = "Restore the data of a previous version of a secret as a new version")]
+    Rollback {
+        #[arg(help = "Path to secret, e.g. 'mp/metrics'")]
+        path: String,
+
+        #[arg(long, help = "Version to restore")]
+        to: u64,
+
+        #[arg(short = 'a', long, help = "Use AWS profile", num_args = 0..=1, default_missing_value = "PROMPT")]
+        // Will be PROMPT if the user included the flag without a value, None if they didn't include the flag at all
+        aws_profile: Option<String>,
     },
 }
 
@@ -236,6 +524,95 @@ pub enum LoggingAction {
     },
 }
 
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArgoAction {
+    #[command(about = "Show the health, replicas and images of each workload in an application")]
+    App {
+        #[arg(help = "Name of the ArgoCD application, e.g. 'web-bff'")]
+        name: String,
+
+        #[arg(long, help = "Show the commit, PR and image tag bump behind each deployment instead")]
+        history: bool,
+
+        #[arg(short, long, help = "ArgoCD instance (if omitted, inferred from the AWS profile or prompted)", value_parser = ["dev", "stage", "prod"])]
+        env: Option<String>,
+    },
+    #[command(about = "Compare the image tag of every application across all ArgoCD instances")]
+    Matrix {
+        #[arg(long, alias = "differs-from-prod", help = "Only show apps whose dev and stage versions both differ from prod (not necessarily newer, since tags aren't ordered)")]
+        promotable: bool,
+
+        #[arg(long, help = "ArgoCD project whose apps are shown (if omitted, uses all configured projects)")]
+        project: Option<String>,
+    },
+    #[command(about = "Sync an application and follow the operation until it completes")]
+    Sync {
+        #[arg(help = "Name of the ArgoCD application, e.g. 'web-bff'")]
+        app: String,
+
+        #[arg(long, help = "Delete resources that are no longer defined in git")]
+        prune: bool,
+
+        #[arg(long, help = "Preview the sync without applying any changes")]
+        dry_run: bool,
+
+        #[arg(long, default_value = "10m", value_parser = parse_duration, help = "Give up waiting for the operation to finish after this long, e.g. '15m'")]
+        timeout: Duration,
+
+        #[arg(short, long, help = "ArgoCD instance (if omitted, inferred from the AWS profile or prompted)", value_parser = ["dev", "stage", "prod"])]
+        env: Option<String>,
+    },
+    #[command(about = "Compare an application against the latest state of git")]
+    Refresh {
+        #[arg(help = "Name of the ArgoCD application, e.g. 'web-bff'")]
+        app: String,
+
+        #[arg(long, help = "Also invalidate cached manifests and regenerate them")]
+        hard: bool,
+
+        #[arg(long, default_value = "10m", value_parser = parse_duration, help = "Give up waiting for the operation to finish after this long, e.g. '15m'")]
+        timeout: Duration,
+
+        #[arg(short, long, help = "ArgoCD instance (if omitted, inferred from the AWS profile or prompted)", value_parser = ["dev", "stage", "prod"])]
+        env: Option<String>,
+    },
+    #[command(about = "Roll an application back to a previously deployed revision")]
+    Rollback {
+        #[arg(help = "Name of the ArgoCD application, e.g. 'web-bff'")]
+        app: String,
+
+        #[arg(long, help = "History ID to roll back to (if omitted, will prompt)")]
+        to: Option<u64>,
+
+        #[arg(long, default_value = "10m", value_parser = parse_duration, help = "Give up waiting for the operation to finish after this long, e.g. '15m'")]
+        timeout: Duration,
+
+        #[arg(short, long, help = "ArgoCD instance (if omitted, inferred from the AWS profile or prompted)", value_parser = ["dev", "stage", "prod"])]
+        env: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AuthAction {
+    #[command(about = "Show the identity and expiry of all cached credentials")]
+    Status,
+    #[command(about = "Revoke and delete cached credentials")]
+    #[command(group(ArgGroup::new("targets").required(true).multiple(true).args(["argo", "vault", "sso", "all"])))]
+    Logout {
+        #[arg(long, help = "ArgoCD instance to log out of", value_parser = ["dev", "stage", "prod"])]
+        argo: Option<String>,
+
+        #[arg(long, help = "Vault instance to log out of", value_parser = ["non-prod", "prod"])]
+        vault: Option<String>,
+
+        #[arg(long, help = "Delete cached AWS SSO tokens")]
+        sso: bool,
+
+        #[arg(long, help = "Log out of everything")]
+        all: bool,
+    },
+}
+
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BazelAction {
     #[command(about = "Run a Bazel target")]
@@ -245,6 +622,24 @@ pub enum BazelAction {
     },
 }
 
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GhAction {
+    #[command(about = "Show the latest GitHub Actions runs of a repo and the image tags that they're expected to publish")]
+    Runs {
+        #[arg(help = "Name of the repo in the agilityrobotics org, e.g. 'web-bff'")]
+        repo: String,
+
+        #[arg(short, long, default_value = "main", help = "Branch whose runs are shown")]
+        branch: String,
+
+        #[arg(long, help = "Name or file name of the workflow that builds the image (defaults to github.build_workflow in the config)")]
+        workflow: Option<String>,
+
+        #[arg(short, long, help = "Follow the latest build until it finishes, exiting with an error unless it succeeds")]
+        wait: bool,
+    },
+}
+
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum InfluxAction {
     #[command(about = "Launch the InfluxDB UI")]
@@ -290,6 +685,39 @@ pub enum InfluxAction {
     },
 }
 
+fn parse_secret_field(input: &str) -> Result<(String, Option<String>), String> {
+    let (key, value) = match input.split_once('=') {
+        Some((key, value)) => (key, Some(value.to_string())),
+        None => (input, None),
+    };
+
+    if key.is_empty() {
+        return Err(format!("Invalid field '{}', expected KEY=VALUE or KEY", input));
+    }
+    Ok((key.to_string(), value))
+}
+
+fn parse_duration(input: &str) -> Result<Duration, String> {
+    // Plain numbers are treated as seconds
+    let (value, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => input.split_at(index),
+        None => (input, "s"),
+    };
+
+    let value: u64 = value.parse().map_err(|_| format!("Invalid duration '{}', expected e.g. '5s' or '1m'", input))?;
+    let seconds = match unit {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 3600,
+        _ => return Err(format!("Invalid duration unit in '{}', expected s, m or h", input)),
+    };
+
+    if seconds == 0 {
+        return Err("Duration must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
 fn parse_datetime(input: &str) -> Result<DateTime<Utc>, String> {
     // Try parsing as milliseconds since epoch
     if let Ok(millis) = input.parse::<i64>() {
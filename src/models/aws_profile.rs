@@ -1,5 +1,6 @@
 use std::convert::From;
 use aws_runtime::env_config::section::EnvConfigSections;
+use crate::models::errors::ArcError;
 use crate::models::get_env_configs;
 use crate::models::influx::InfluxInstance;
 use crate::models::rds::RdsInstance;
@@ -30,14 +31,27 @@ impl From<&str> for AwsAccount {
 }
 
 impl AwsAccount {
-    pub fn vault_instance(&self) -> VaultInstance {
+    pub fn name(&self) -> &str {
         match self {
-            AwsAccount::DataPlatform => VaultInstance::Prod,
-            AwsAccount::Dev => VaultInstance::NonProd,
-            AwsAccount::Prod => VaultInstance::Prod,
-            AwsAccount::Sandbox => VaultInstance::NonProd,
-            AwsAccount::Stage => VaultInstance::NonProd,
-            _ => panic!("No Vault instance exists for this AWS account: {:?}", self),
+            AwsAccount::DataPlatform => "data-platform",
+            AwsAccount::Dev => "dev",
+            AwsAccount::Iot => "iot",
+            AwsAccount::Prod => "prod",
+            AwsAccount::Sandbox => "sandbox",
+            AwsAccount::Stage => "stage",
+        }
+    }
+
+    pub fn vault_instance(&self) -> Result<VaultInstance, ArcError> {
+        match self {
+            AwsAccount::DataPlatform => Ok(VaultInstance::Prod),
+            AwsAccount::Dev => Ok(VaultInstance::NonProd),
+            AwsAccount::Prod => Ok(VaultInstance::Prod),
+            AwsAccount::Sandbox => Ok(VaultInstance::NonProd),
+            AwsAccount::Stage => Ok(VaultInstance::NonProd),
+            AwsAccount::Iot => Err(ArcError::UserInputError(
+                format!("No Vault instance exists for AWS account '{}'", self.name())
+            )),
         }
     }
 
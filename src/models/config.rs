@@ -1,25 +1,218 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::models::errors::ArcError;
+use crate::models::vault::VaultAuthMethod;
 
 #[derive(Debug, Deserialize)]
 pub struct CliConfig {
+    #[serde(default)]
+    pub(crate) argo: ArgoConfig,
+
     #[serde(default)]
     pub(crate) bazel: BazelConfig,
 
+    #[serde(default)]
+    pub(crate) credentials: CredentialsConfig,
+
+    #[serde(default)]
+    pub(crate) github: GithubConfig,
+
     #[serde(default, rename = "port-forward")]
     pub(crate) port_forward: PortForwardConfig,
+
+    /// Overrides for each Vault instance, keyed by instance name (e.g. "non-prod")
+    #[serde(default)]
+    pub(crate) vault: HashMap<String, VaultInstanceConfig>,
 }
 
 impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
+            argo: ArgoConfig::default(),
             bazel: BazelConfig::default(),
+            credentials: CredentialsConfig::default(),
+            github: GithubConfig::default(),
             port_forward: PortForwardConfig { groups: Vec::new() },
+            vault: HashMap::new(),
         }
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct ArgoConfig {
+    /// Projects whose applications are shown, unless a single one is selected with --project
+    #[serde(default = "default_argo_projects")]
+    pub(crate) projects: Vec<String>,
+
+    /// The resource whose image is an app's deployed version, keyed by app name. Apps that aren't
+    /// listed use the Deployment with the same name as the app. Configured entries are merged over
+    /// the defaults rather than replacing them.
+    #[serde(default = "default_argo_resources", deserialize_with = "deserialize_argo_resources")]
+    pub(crate) resources: HashMap<String, ArgoResourceIdentity>,
+
+    /// Resource kinds whose images ArgoCD doesn't report, so they're read from the live manifest.
+    /// Configured rules take precedence over the defaults for the same group and kind.
+    #[serde(default = "default_argo_image_rules", deserialize_with = "deserialize_argo_image_rules")]
+    pub(crate) image_rules: Vec<ArgoImageRule>,
+
+    /// TLS settings for each ArgoCD instance, keyed by instance name (e.g. "dev")
+    #[serde(default)]
+    pub(crate) instances: HashMap<String, ArgoInstanceConfig>,
+}
+
+impl ArgoConfig {
+    /// The projects to query, either the one selected by the user or all configured projects
+    pub(crate) fn projects(&self, selected: Option<&str>) -> Vec<String> {
+        match selected {
+            Some(project) => vec![project.to_string()],
+            None => self.projects.clone(),
+        }
+    }
+
+    pub(crate) fn resource_identity(&self, app_name: &str) -> ArgoResourceIdentity {
+        self.resources.get(app_name)
+            .cloned()
+            .unwrap_or_else(|| ArgoResourceIdentity::deployment(app_name))
+    }
+
+    pub(crate) fn image_rule(&self, resource: &ArgoResourceIdentity) -> Option<&ArgoImageRule> {
+        self.image_rules.iter().find(|rule| rule.group == resource.group && rule.kind == resource.kind)
+    }
+
+    pub(crate) fn instance(&self, name: &str) -> ArgoInstanceConfig {
+        self.instances.get(name).cloned().unwrap_or_default()
+    }
+}
+
+impl Default for ArgoConfig {
+    fn default() -> Self {
+        ArgoConfig {
+            projects: default_argo_projects(),
+            resources: default_argo_resources(),
+            image_rules: default_argo_image_rules(),
+            instances: HashMap::new(),
+        }
+    }
+}
+
+// Technically a resource is uniquely identified by (Group + Kind + namespace + name + version)
+// but for our purposes we can get away with just using the (Group + Kind + name)
+#[derive(Clone, Debug, Deserialize)]
+pub struct ArgoResourceIdentity {
+    #[serde(default = "default_argo_resource_group")]
+    pub(crate) group: String,
+    #[serde(default = "default_argo_resource_kind")]
+    pub(crate) kind: String,
+    pub(crate) name: String,
+}
+
+impl ArgoResourceIdentity {
+    fn new(group: &str, kind: &str, name: &str) -> Self {
+        ArgoResourceIdentity { group: group.to_string(), kind: kind.to_string(), name: name.to_string() }
+    }
+
+    fn deployment(name: &str) -> Self {
+        ArgoResourceIdentity::new(&default_argo_resource_group(), &default_argo_resource_kind(), name)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ArgoInstanceConfig {
+    /// PEM file of CA certificates to trust, in addition to the system's trusted certificates
+    ca_bundle: Option<String>,
+
+    /// Skip TLS certificate verification entirely. Only meant as a last resort, since tokens are
+    /// then sent over connections that could be intercepted.
+    #[serde(default)]
+    pub(crate) insecure: bool,
+}
+
+impl ArgoInstanceConfig {
+    /// Returns the absolute path to the CA bundle, if one is configured. Expands tilde (~) if present.
+    pub fn ca_bundle(&self) -> Result<Option<PathBuf>, ArcError> {
+        let Some(ca_bundle) = &self.ca_bundle else {
+            return Ok(None);
+        };
+
+        let path = match ca_bundle.strip_prefix("~/") {
+            Some(relative_path) => home::home_dir().ok_or_else(|| ArcError::HomeDirError)?.join(relative_path),
+            None => PathBuf::from(ca_bundle),
+        };
+        Ok(Some(path))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ArgoImageRule {
+    #[serde(default)]
+    pub(crate) group: String,
+    pub(crate) kind: String,
+    pub(crate) version: String,
+    /// Dot separated path to the image within the manifest, where numbers index into lists
+    pub(crate) image_path: String,
+}
+
+impl ArgoImageRule {
+    fn new(group: &str, kind: &str, version: &str, image_path: &str) -> Self {
+        ArgoImageRule {
+            group: group.to_string(),
+            kind: kind.to_string(),
+            version: version.to_string(),
+            image_path: image_path.to_string(),
+        }
+    }
+}
+
+fn default_argo_projects() -> Vec<String> {
+    vec!["arc".to_string()]
+}
+
+fn default_argo_resources() -> HashMap<String, ArgoResourceIdentity> {
+    HashMap::from([
+        ("apollo-server".to_string(), ArgoResourceIdentity::new("apps", "Deployment", "agility-graph")),
+        ("database-migration".to_string(), ArgoResourceIdentity::new("batch", "Job", "flyway")),
+        ("web-bff".to_string(), ArgoResourceIdentity::new("apps", "Deployment", "backend")),
+        ("webhook-integ".to_string(), ArgoResourceIdentity::new("apps", "Deployment", "webhook-integration")),
+        ("workflow-worker".to_string(), ArgoResourceIdentity::new("argoproj.io", "Sensor", "workflow-worker")),
+    ])
+}
+
+fn default_argo_image_rules() -> Vec<ArgoImageRule> {
+    vec![
+        ArgoImageRule::new("argoproj.io", "Sensor", "v1alpha1", "spec.triggers.0.template.k8s.source.resource.spec.templates.0.container.image"),
+        ArgoImageRule::new("batch", "CronJob", "v1", "spec.jobTemplate.spec.template.spec.containers.0.image"),
+        ArgoImageRule::new("apps", "StatefulSet", "v1", "spec.template.spec.containers.0.image"),
+    ]
+}
+
+fn deserialize_argo_resources<'de, D>(deserializer: D) -> Result<HashMap<String, ArgoResourceIdentity>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut resources = default_argo_resources();
+    resources.extend(HashMap::<String, ArgoResourceIdentity>::deserialize(deserializer)?);
+    Ok(resources)
+}
+
+fn deserialize_argo_image_rules<'de, D>(deserializer: D) -> Result<Vec<ArgoImageRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Rules are searched in order, so configured rules go before the defaults that they may override
+    let mut image_rules = Vec::<ArgoImageRule>::deserialize(deserializer)?;
+    image_rules.extend(default_argo_image_rules());
+    Ok(image_rules)
+}
+
+fn default_argo_resource_group() -> String {
+    "apps".to_string()
+}
+
+fn default_argo_resource_kind() -> String {
+    "Deployment".to_string()
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BazelConfig {
     agility_software_repo: Option<String>,
@@ -53,6 +246,76 @@ impl Default for BazelConfig {
     }
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct CredentialsConfig {
+    #[serde(default)]
+    pub(crate) store: CredentialStoreKind,
+}
+
+/// Where cached credentials (e.g. Argo and Vault tokens) are stored
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStoreKind {
+    /// The OS keyring if one is available, otherwise an encrypted file
+    #[default]
+    Auto,
+    /// The OS keyring, such as Keychain Access on MacOS or Secret Service on Linux
+    Keyring,
+    /// An encrypted file in the config directory
+    File,
+    /// Memory only, credentials are discarded when the command exits
+    Memory,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubConfig {
+    /// Name or file name of the workflow that builds and publishes a repo's image, e.g. "build.yaml"
+    pub(crate) build_workflow: Option<String>,
+
+    /// Template of the image tag that a repo's build workflow is expected to publish, which may contain `{sha}`,
+    /// `{short_sha}`, `{run_number}` and `{branch}` placeholders
+    #[serde(default = "default_github_image_tag")]
+    pub(crate) image_tag: String,
+
+    /// Overrides for repos whose images are built or tagged differently, keyed by repo name
+    #[serde(default)]
+    pub(crate) repos: HashMap<String, GithubRepoConfig>,
+}
+
+impl GithubConfig {
+    pub(crate) fn build_workflow(&self, repo: &str) -> Option<&str> {
+        self.repos.get(repo)
+            .and_then(|repo_config| repo_config.build_workflow.as_deref())
+            .or(self.build_workflow.as_deref())
+    }
+
+    pub(crate) fn image_tag_template(&self, repo: &str) -> &str {
+        self.repos.get(repo)
+            .and_then(|repo_config| repo_config.image_tag.as_deref())
+            .unwrap_or(&self.image_tag)
+    }
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        GithubConfig {
+            build_workflow: None,
+            image_tag: default_github_image_tag(),
+            repos: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GithubRepoConfig {
+    pub(crate) build_workflow: Option<String>,
+    pub(crate) image_tag: Option<String>,
+}
+
+fn default_github_image_tag() -> String {
+    "{short_sha}".to_string()
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PortForwardConfig {
     pub(crate) groups: Vec<ServiceGroup>,
@@ -75,4 +338,18 @@ pub struct Service {
     pub(crate) name: String,
     pub(crate) namespace: String,
     pub(crate) local_port: u16,
-}
\ No newline at end of file
+}
+#[derive(Debug, Default, Deserialize)]
+pub struct VaultInstanceConfig {
+    pub(crate) address: Option<String>,
+    pub(crate) auth_method: Option<VaultAuthMethod>,
+    pub(crate) auth_mount: Option<String>,
+    pub(crate) auth_namespace: Option<String>,
+    pub(crate) oidc_role: Option<String>,
+    pub(crate) kv_mount: Option<String>,
+    pub(crate) database_mount: Option<String>,
+
+    /// Secrets namespace for each AWS account, keyed by account name (e.g. "sandbox")
+    #[serde(default)]
+    pub(crate) namespaces: HashMap<String, String>,
+}
@@ -52,6 +52,9 @@ pub enum ArcError {
     #[error("Command execution error: {0}")]
     CommandExecutionError(String),
 
+    #[error("Credential store error: {0}")]
+    CredentialStoreError(String),
+
     #[error("Error: {0}")]
     Error(#[from] Box<dyn std::error::Error + Send + Sync>),
 
@@ -106,6 +109,12 @@ pub enum ArcError {
     #[error("Keyring error: {0}")]
     KeyringError(#[from] keyring::Error),
 
+    #[error("OIDC login error: {0}")]
+    OidcCallbackError(String),
+
+    #[error("OpenIDConnect claims verification error: {0}")]
+    OpenIdConnectClaimsError(#[from] openidconnect::ClaimsVerificationError),
+
     #[error("OpenIDConnect config error: {0}")]
     OpenIdConnectConfigError(#[from] openidconnect::ConfigurationError),
 
@@ -139,6 +148,15 @@ pub enum ArcError {
     #[error("Unable to extract query param: {1}, from URL: {0}")]
     UrlQueryParamError(Url, String),
 
+    #[error("Vault secret '{0}' was modified after it was read, please retry to review the latest changes")]
+    VaultConflict(String),
+
+    #[error("Permission denied for Vault path '{0}', your credentials are valid but not authorized for this path")]
+    VaultPermissionDenied(String),
+
+    #[error("Vault secret '{0}' not found")]
+    VaultSecretNotFound(String),
+
     #[error("Vault error: {0}")]
     VaultError(#[from] vaultrs::error::ClientError),
 
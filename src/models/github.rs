@@ -1,18 +1,21 @@
 use std::time::{SystemTime, UNIX_EPOCH};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
+use console::style;
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
-use openidconnect::http::header::{AUTHORIZATION, USER_AGENT};
 use serde::{Deserialize, Serialize};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
 use crate::models::errors::ArcError;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Installation {
     pub(crate) id: u64,
+    pub(crate) account: GithubUser,
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct InstallationToken {
     pub(crate) token: String,
+    pub(crate) expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,14 +25,168 @@ pub(crate) struct Claims {
     pub(crate) iss: String,
 }
 
+/// How to find the PR whose changes should be watched
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PrLocator {
+    Number(u32),
+    /// Prompt to select one of the PRs opened within the given duration
+    RecentlyOpened(std::time::Duration),
+    Branch(String),
+    Commit(String),
+    /// The current branch of the local checkout in the working directory
+    CurrentBranch,
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct GithubPr {
     pub(crate) number: u64,
     pub(crate) title: String,
+    pub(crate) state: String,
+    pub(crate) html_url: String,
     pub(crate) created_at: DateTime<Utc>,
+    pub(crate) merged_at: Option<DateTime<Utc>>,
     pub(crate) user: GithubUser,
 }
 
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubCommit {
+    pub(crate) sha: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubCommitDetails {
+    #[serde(default)]
+    pub(crate) files: Vec<GithubPrFile>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubRepo {
+    pub(crate) default_branch: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubRef {
+    pub(crate) object: GithubCommit,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubTree {
+    pub(crate) tree: Vec<GithubTreeEntry>,
+    pub(crate) truncated: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubTreeEntry {
+    pub(crate) path: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubFileContent {
+    pub(crate) sha: String,
+    pub(crate) content: String,
+}
+
+impl GithubFileContent {
+    /// The file's content, which the contents API returns base64 encoded and wrapped across lines
+    pub(crate) fn decoded(&self) -> Result<String, ArcError> {
+        let encoded: String = self.content.split_whitespace().collect();
+        let bytes = STANDARD.decode(encoded)
+            .map_err(|e| ArcError::UserInputError(format!("Unable to decode GitHub file content: {}", e)))?;
+        String::from_utf8(bytes)
+            .map_err(|e| ArcError::UserInputError(format!("GitHub file content isn't UTF-8: {}", e)))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GithubWorkflowRuns {
+    pub(crate) workflow_runs: Vec<GithubWorkflowRun>,
+}
+
+/// A GitHub Actions workflow run, e.g. the build that publishes a service's image
+#[derive(Deserialize, Debug)]
+pub struct GithubWorkflowRun {
+    pub(crate) id: u64,
+    pub(crate) name: Option<String>,
+    /// Path of the workflow's file, e.g. ".github/workflows/build.yaml"
+    pub(crate) path: String,
+    pub(crate) run_number: u64,
+    pub(crate) head_branch: Option<String>,
+    pub(crate) head_sha: String,
+    pub(crate) status: String,
+    pub(crate) conclusion: Option<String>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) html_url: String,
+}
+
+impl GithubWorkflowRun {
+    pub(crate) fn header() -> String {
+        format!("{:<8} {:<30} {:<12} {:<10} {:<20} {:<30}", "Run", "Workflow", "Status", "Commit", "Started", "Expected Tag")
+    }
+
+    /// Whether the run belongs to a workflow, given either its name or the name of its file
+    pub(crate) fn is_workflow(&self, workflow: &str) -> bool {
+        let file_name = self.path.rsplit('/').next();
+        self.name.as_deref() == Some(workflow) || file_name == Some(workflow) || self.path == workflow
+    }
+
+    pub(crate) fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+
+    pub(crate) fn is_successful(&self) -> bool {
+        self.conclusion.as_deref() == Some("success")
+    }
+
+    pub(crate) fn short_sha(&self) -> &str {
+        &self.head_sha[..self.head_sha.len().min(7)]
+    }
+
+    /// The status of a run in progress, or how it concluded once it's completed
+    pub(crate) fn outcome(&self) -> &str {
+        match (self.is_completed(), self.conclusion.as_deref()) {
+            (true, Some(conclusion)) => conclusion,
+            _ => &self.status,
+        }
+    }
+
+    /// The image tag that the run is expected to publish, according to a template that may contain `{sha}`,
+    /// `{short_sha}`, `{run_number}` and `{branch}` placeholders. GitHub doesn't expose what a run actually
+    /// pushed, so this is only as accurate as the template.
+    pub(crate) fn image_tag(&self, template: &str) -> String {
+        template
+            .replace("{sha}", &self.head_sha)
+            .replace("{short_sha}", self.short_sha())
+            .replace("{run_number}", &self.run_number.to_string())
+            .replace("{branch}", self.head_branch.as_deref().unwrap_or_default())
+    }
+
+    /// Table row, where the expected image tag is only shown for successful runs of the build workflow,
+    /// since no other runs publish one
+    pub(crate) fn row(&self, image_tag_template: Option<&str>) -> String {
+        let image_tag = match image_tag_template {
+            Some(template) if self.is_successful() => self.image_tag(template),
+            _ => "-".to_string(),
+        };
+
+        // Pad before styling, otherwise the escape codes would count towards the column width
+        let outcome = format!("{:<12}", self.outcome());
+        let outcome = match (self.is_completed(), self.is_successful()) {
+            (true, true) => style(outcome).green().to_string(),
+            (true, false) => style(outcome).red().to_string(),
+            (false, _) => style(outcome).yellow().to_string(),
+        };
+        format!(
+            "{:<8} {:<30} {} {:<10} {:<20} {:<30}",
+            format!("#{}", self.run_number),
+            self.name.as_deref().unwrap_or("-"),
+            outcome,
+            self.short_sha(),
+            self.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+            image_tag
+        )
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct GithubUser {
     pub(crate) login: String,
@@ -43,49 +200,70 @@ pub struct GithubPrFile {
     pub(crate) patch: Option<String>,
 }
 
-pub async fn get_installation_id(
-    client: &reqwest::Client,
-    app_id: &str,
-    private_key: &str,
-) -> Result<String, ArcError> {
-    // Generate JWT (same as in get_github_app_token)
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-    let claims = Claims {
-        iat: now,
-        exp: now + 600,
-        iss: app_id.to_string(),
-    };
+impl GithubPrFile {
+    /// The image tag set by the lines that the patch adds, if any
+    pub(crate) fn added_tag(&self) -> Option<String> {
+        self.patch_tag('+')
+    }
 
-    let key = EncodingKey::from_rsa_pem(private_key.as_bytes())?;
-    let jwt = encode(&Header::new(Algorithm::RS256), &claims, &key)?;
-
-    // Get installations for this app
-    let url = "https://api.github.com/app/installations";
-
-    let response = client
-        .get(url)
-        .header(USER_AGENT, "rust-github-pr-list")
-        .header("Accept", "application/vnd.github.v3+json")
-        .header(AUTHORIZATION, format!("Bearer {}", jwt))
-        .send()
-        .await?;
-
-    let installations: Vec<Installation> = response.json().await?;
-
-    // Use the first installation (typically there's only one for org apps)
-    installations
-        .first()
-        .map(|i| i.id.to_string())
-        .ok_or_else(|| ArcError::UserInputError("No GitHub App installations found".to_string()))
-}
-
-pub async fn get_github_app_token(
-    client: &reqwest::Client,
-    app_id: &str,
-    private_key: &str,
-    installation_id: &str,
-) -> Result<String, ArcError> {
-    // Generate JWT
+    /// The image tag that was replaced by the patch, if any
+    pub(crate) fn removed_tag(&self) -> Option<String> {
+        self.patch_tag('-')
+    }
+
+    fn patch_tag(&self, marker: char) -> Option<String> {
+        let patch = self.patch.as_ref()?;
+        let file_header = marker.to_string().repeat(3);
+
+        // Search the patch's lines for a "tag" key, regardless of how deeply it's indented
+        patch.lines()
+            .filter(|line| !line.starts_with(&file_header))
+            .filter_map(|line| line.strip_prefix(marker))
+            .find_map(parse_tag_line)
+    }
+}
+
+/// The image tag set by a version file, e.g. `charts/<project>/<app>/envs/.../prod/version.yaml`
+pub(crate) fn version_file_tag(content: &str) -> Option<String> {
+    content.lines().find_map(parse_tag_line)
+}
+
+/// Replaces the image tag set by a version file, keeping the line's indentation, quoting and any
+/// trailing comment, or returns None if the file doesn't set a tag
+pub(crate) fn replace_version_file_tag(content: &str, tag: &str) -> Option<String> {
+    let mut replaced = false;
+    let lines: Vec<String> = content.split('\n')
+        .map(|line| {
+            if replaced || parse_tag_line(line).is_none() {
+                return line.to_string();
+            }
+            replaced = true;
+
+            let (key, value) = line.split_once("tag:").unwrap_or_default();
+            let (value, comment) = match value.find(" #") {
+                Some(index) => value.split_at(index),
+                None => (value, ""),
+            };
+            let quote = value.trim().chars().next().filter(|c| *c == '"' || *c == '\'').map(String::from).unwrap_or_default();
+            format!("{}tag: {}{}{}{}", key, quote, tag, quote, comment.trim_end())
+        })
+        .collect();
+
+    replaced.then(|| lines.join("\n"))
+}
+
+fn parse_tag_line(line: &str) -> Option<String> {
+    let value = line.trim_start().strip_prefix("tag:")?;
+
+    // Drop any trailing comment, then remove quotes if present
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    let tag = value.trim_matches('"').trim_matches('\'');
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
+/// Signs a short-lived JWT that authenticates as the GitHub App itself, which can only be used to
+/// look up the app's installations and mint installation tokens
+pub fn github_app_jwt(app_id: &str, private_key: &str) -> Result<String, ArcError> {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     let claims = Claims {
         iat: now,
@@ -94,22 +272,5 @@ pub async fn get_github_app_token(
     };
 
     let key = EncodingKey::from_rsa_pem(private_key.as_bytes())?;
-    let jwt = encode(&Header::new(Algorithm::RS256), &claims, &key)?;
-
-    // Exchange JWT for installation access token
-    let url = format!(
-        "https://api.github.com/app/installations/{}/access_tokens",
-        installation_id
-    );
-
-    let response = client
-        .post(&url)
-        .header(USER_AGENT, "rust-github-pr-list")
-        .header("Accept", "application/vnd.github.v3+json")
-        .header(AUTHORIZATION, format!("Bearer {}", jwt))
-        .send()
-        .await?;
-
-    let token_response: InstallationToken = response.json().await?;
-    Ok(token_response.token)
-}
\ No newline at end of file
+    Ok(encode(&Header::new(Algorithm::RS256), &claims, &key)?)
+}
@@ -2,17 +2,29 @@ use std;
 use std::convert::From;
 use chrono::{DateTime, NaiveDate, Utc};
 use crate::models::args::PROMPT;
+use crate::models::argo::ArgoOperation;
+use crate::models::github::PrLocator;
 use crate::models::aws_profile::AwsAccount;
 use crate::models::log_level::LogLevel;
+use crate::models::rds::DbRole;
+use crate::models::vault::VaultWrite;
 use crate::tasks::Task;
 use crate::tasks::create_tab_completions::CreateTabCompletionsTask;
 use crate::tasks::get_aws_secret::GetAwsSecretTask;
 use crate::tasks::get_vault_secret::GetVaultSecretTask;
 use crate::tasks::launch_influx::LaunchInfluxTask;
+use crate::tasks::get_argo_app_details::GetArgoAppDetailsTask;
+use crate::tasks::get_argo_app_history::GetArgoAppHistoryTask;
 use crate::tasks::get_argo_app_statuses::GetArgoAppStatusesTask;
+use crate::tasks::get_argo_version_matrix::GetArgoVersionMatrixTask;
+use crate::tasks::run_argo_operation::RunArgoOperationTask;
+use crate::tasks::select_argo_instance::SelectArgoInstanceTask;
 use crate::tasks::get_github_pr_files::GetGithubPrFilesTask;
+use crate::tasks::get_github_runs::GetGithubRunsTask;
+use crate::tasks::get_github_token::GetGithubTokenTask;
 use crate::tasks::perform_sso::PerformSsoTask;
 use crate::tasks::port_forward::PortForwardTask;
+use crate::tasks::promote_apps::PromoteAppsTask;
 use crate::tasks::influx_dump::InfluxDumpTask;
 use crate::tasks::run_pgcli::RunPgcliTask;
 use crate::tasks::run_bazel_target::RunBazelTargetTask;
@@ -23,6 +35,13 @@ use crate::tasks::select_kube_context::SelectKubeContextTask;
 use crate::tasks::select_organization::SelectOrganizationTask;
 use crate::tasks::select_rds_instance::SelectRdsInstanceTask;
 use crate::tasks::logging::LoggingTask;
+use crate::tasks::get_auth_status::GetAuthStatusTask;
+use crate::tasks::logout::LogoutTask;
+use crate::tasks::write_vault_secret::WriteVaultSecretTask;
+use crate::tasks::diff_vault_secret::DiffVaultSecretTask;
+use crate::tasks::get_vault_secret_history::GetVaultSecretHistoryTask;
+use crate::tasks::list_vault_secrets::ListVaultSecretsTask;
+use crate::tasks::find_vault_secrets::FindVaultSecretsTask;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Goal {
@@ -67,15 +86,20 @@ impl Goal {
         Goal::new_terminal(GoalType::AwsSecretKnown, params)
     }
 
-    pub fn github_pr_files_known(
-        repo: String,
-        pull_request: Option<u32>,
-        lookback_duration: Option<std::time::Duration>,
-    ) -> Self {
-        let params = GoalParams::GithubPrFilesKnown { repo, pull_request, lookback_duration };
+    pub fn github_pr_files_known(repo: String, pr_locator: PrLocator) -> Self {
+        let params = GoalParams::GithubPrFilesKnown { repo, pr_locator };
         Goal::new(GoalType::GithubPrFilesKnown, params)
     }
 
+    pub fn terminal_github_runs_known(repo: String, branch: String, workflow: Option<String>, wait: bool) -> Self {
+        let params = GoalParams::GithubRunsKnown { repo, branch, workflow, wait };
+        Goal::new_terminal(GoalType::GithubRunsKnown, params)
+    }
+
+    pub fn github_token_known() -> Self {
+        Goal::new(GoalType::GithubTokenKnown, GoalParams::None)
+    }
+
     pub fn influx_instance_selected(aws_profile: Option<String>) -> Self {
         let params = GoalParams::InfluxInstanceSelected { aws_profile };
         Goal::new(GoalType::InfluxInstanceSelected, params)
@@ -134,11 +158,16 @@ impl Goal {
         Goal::new(GoalType::OrganizationSelected, GoalParams::None)
     }
 
-    pub fn terminal_pgcli_running(aws_profile: Option<String>) -> Self {
-        let params = GoalParams::PgcliRunning { aws_profile };
+    pub fn terminal_pgcli_running(aws_profile: Option<String>, role: Option<DbRole>) -> Self {
+        let params = GoalParams::PgcliRunning { aws_profile, role };
         Goal::new_terminal(GoalType::PgcliRunning, params)
     }
 
+    pub fn terminal_apps_promoted(apps: Vec<String>, from: String, to: String, branch: String) -> Self {
+        let params = GoalParams::AppsPromoted { apps, from, to, branch };
+        Goal::new_terminal(GoalType::AppsPromoted, params)
+    }
+
     pub fn port_forward_established(service: String, kube_context: Option<String>) -> Self {
         let params = GoalParams::PortForwardEstablished {
             namespace: None,
@@ -175,11 +204,55 @@ impl Goal {
         Goal::new_terminal(GoalType::TabCompletionsExist, GoalParams::None)
     }
 
-    pub fn terminal_argo(pull_request: Option<u32>) -> Self {
-        let params = GoalParams::ArgoStatusesKnown { pull_request };
+    pub fn terminal_argo(
+        pull_request: Option<PrLocator>,
+        project: Option<String>,
+        timeout: Option<std::time::Duration>,
+        watch_interval: Option<std::time::Duration>,
+    ) -> Self {
+        let params = GoalParams::ArgoStatusesKnown { pull_request, project, timeout, watch_interval };
         Goal::new_terminal(GoalType::ArgoStatusKnown, params)
     }
 
+    pub fn terminal_argo_app_details_known(app: String, env: Option<String>) -> Self {
+        let params = GoalParams::ArgoAppDetailsKnown { app, env };
+        Goal::new_terminal(GoalType::ArgoAppDetailsKnown, params)
+    }
+
+    pub fn terminal_argo_version_matrix_known(promotable: bool, project: Option<String>) -> Self {
+        let params = GoalParams::ArgoVersionMatrixKnown { promotable, project };
+        Goal::new_terminal(GoalType::ArgoVersionMatrixKnown, params)
+    }
+
+    pub fn terminal_argo_app_history_known(app: String, env: Option<String>) -> Self {
+        let params = GoalParams::ArgoAppHistoryKnown { app, env };
+        Goal::new_terminal(GoalType::ArgoAppHistoryKnown, params)
+    }
+
+    pub fn argo_instance_selected(env: Option<String>) -> Self {
+        let params = GoalParams::ArgoInstanceSelected { env };
+        Goal::new(GoalType::ArgoInstanceSelected, params)
+    }
+
+    pub fn terminal_argo_operation_completed(
+        app: String,
+        operation: ArgoOperation,
+        timeout: std::time::Duration,
+        env: Option<String>,
+    ) -> Self {
+        let params = GoalParams::ArgoOperationCompleted { app, operation, timeout, env };
+        Goal::new_terminal(GoalType::ArgoOperationCompleted, params)
+    }
+
+    pub fn terminal_auth_status_known() -> Self {
+        Goal::new_terminal(GoalType::AuthStatusKnown, GoalParams::None)
+    }
+
+    pub fn terminal_credentials_cleared(argo: Option<String>, vault: Option<String>, sso: bool, all: bool) -> Self {
+        let params = GoalParams::CredentialsCleared { argo, vault, sso, all };
+        Goal::new_terminal(GoalType::CredentialsCleared, params)
+    }
+
     pub fn terminal_bazel_target_running(target: String) -> Self {
         let params = GoalParams::BazelTargetRunning { target, tear_down: false };
         Goal::new_terminal(GoalType::BazelTargetRunning, params)
@@ -204,6 +277,31 @@ impl Goal {
         let params = GoalParams::VaultSecretKnown { path, field, aws_account: None, aws_profile };
         Goal::new_terminal(GoalType::VaultSecretKnown, params)
     }
+
+    pub fn terminal_vault_secret_history_known(path: String, aws_profile: Option<String>) -> Self {
+        let params = GoalParams::VaultSecretHistoryKnown { path, aws_profile };
+        Goal::new_terminal(GoalType::VaultSecretHistoryKnown, params)
+    }
+
+    pub fn terminal_vault_secret_diff_known(path: String, from: u64, to: Option<u64>, aws_profile: Option<String>) -> Self {
+        let params = GoalParams::VaultSecretDiffKnown { path, from, to, aws_profile };
+        Goal::new_terminal(GoalType::VaultSecretDiffKnown, params)
+    }
+
+    pub fn terminal_vault_secret_paths_known(prefix: Option<String>, recursive: bool, aws_profile: Option<String>) -> Self {
+        let params = GoalParams::VaultSecretPathsKnown { prefix, recursive, aws_profile };
+        Goal::new_terminal(GoalType::VaultSecretPathsKnown, params)
+    }
+
+    pub fn terminal_vault_secret_matches_known(pattern: String, prefix: Option<String>, aws_profile: Option<String>) -> Self {
+        let params = GoalParams::VaultSecretMatchesKnown { pattern, prefix, aws_profile };
+        Goal::new_terminal(GoalType::VaultSecretMatchesKnown, params)
+    }
+
+    pub fn terminal_vault_secret_written(path: String, write: VaultWrite, aws_profile: Option<String>) -> Self {
+        let params = GoalParams::VaultSecretWritten { path, write, aws_profile };
+        Goal::new_terminal(GoalType::VaultSecretWritten, params)
+    }
 }
 
 impl From<&Goal> for String {
@@ -215,11 +313,21 @@ impl From<&Goal> for String {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GoalType {
     ActuatorServiceSelected,
+    AppsPromoted,
+    ArgoAppDetailsKnown,
+    ArgoAppHistoryKnown,
+    ArgoInstanceSelected,
+    ArgoOperationCompleted,
     ArgoStatusKnown,
+    ArgoVersionMatrixKnown,
+    AuthStatusKnown,
     AwsProfileSelected,
     AwsSecretKnown,
     BazelTargetRunning,
+    CredentialsCleared,
     GithubPrFilesKnown,
+    GithubRunsKnown,
+    GithubTokenKnown,
     InfluxInstanceSelected,
     InfluxLaunched,
     InfluxDumpCompleted,
@@ -232,18 +340,33 @@ pub enum GoalType {
     RdsInstanceSelected,
     SsoTokenValid,
     TabCompletionsExist,
+    VaultSecretDiffKnown,
+    VaultSecretHistoryKnown,
     VaultSecretKnown,
+    VaultSecretMatchesKnown,
+    VaultSecretPathsKnown,
+    VaultSecretWritten,
 }
 
 impl GoalType {
     pub fn to_task(&self) -> Box<dyn Task> {
         match self {
             GoalType::ActuatorServiceSelected => Box::new(SelectActuatorServiceTask),
+            GoalType::AppsPromoted => Box::new(PromoteAppsTask),
+            GoalType::ArgoAppDetailsKnown => Box::new(GetArgoAppDetailsTask),
+            GoalType::ArgoAppHistoryKnown => Box::new(GetArgoAppHistoryTask),
+            GoalType::ArgoInstanceSelected => Box::new(SelectArgoInstanceTask),
+            GoalType::ArgoOperationCompleted => Box::new(RunArgoOperationTask),
             GoalType::ArgoStatusKnown => Box::new(GetArgoAppStatusesTask),
+            GoalType::ArgoVersionMatrixKnown => Box::new(GetArgoVersionMatrixTask),
+            GoalType::AuthStatusKnown => Box::new(GetAuthStatusTask),
             GoalType::AwsProfileSelected => Box::new(SelectAwsProfileTask),
             GoalType::AwsSecretKnown => Box::new(GetAwsSecretTask),
             GoalType::BazelTargetRunning => Box::new(RunBazelTargetTask),
+            GoalType::CredentialsCleared => Box::new(LogoutTask),
             GoalType::GithubPrFilesKnown => Box::new(GetGithubPrFilesTask),
+            GoalType::GithubRunsKnown => Box::new(GetGithubRunsTask),
+            GoalType::GithubTokenKnown => Box::new(GetGithubTokenTask),
             GoalType::InfluxInstanceSelected => Box::new(SelectInfluxInstanceTask),
             GoalType::InfluxLaunched => Box::new(LaunchInfluxTask),
             GoalType::InfluxDumpCompleted => Box::new(InfluxDumpTask),
@@ -256,7 +379,12 @@ impl GoalType {
             GoalType::RdsInstanceSelected => Box::new(SelectRdsInstanceTask),
             GoalType::SsoTokenValid => Box::new(PerformSsoTask),
             GoalType::TabCompletionsExist => Box::new(CreateTabCompletionsTask),
+            GoalType::VaultSecretDiffKnown => Box::new(DiffVaultSecretTask),
+            GoalType::VaultSecretHistoryKnown => Box::new(GetVaultSecretHistoryTask),
             GoalType::VaultSecretKnown => Box::new(GetVaultSecretTask),
+            GoalType::VaultSecretMatchesKnown => Box::new(FindVaultSecretsTask),
+            GoalType::VaultSecretPathsKnown => Box::new(ListVaultSecretsTask),
+            GoalType::VaultSecretWritten => Box::new(WriteVaultSecretTask),
         }
     }
 }
@@ -269,8 +397,38 @@ impl From<GoalType> for String {
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum GoalParams {
+    AppsPromoted {
+        apps: Vec<String>,
+        from: String,
+        to: String,
+        branch: String,
+    },
+    ArgoAppDetailsKnown {
+        app: String,
+        env: Option<String>,
+    },
+    ArgoAppHistoryKnown {
+        app: String,
+        env: Option<String>,
+    },
+    ArgoInstanceSelected {
+        env: Option<String>,
+    },
+    ArgoOperationCompleted {
+        app: String,
+        operation: ArgoOperation,
+        timeout: std::time::Duration,
+        env: Option<String>,
+    },
     ArgoStatusesKnown {
-        pull_request: Option<u32>,
+        pull_request: Option<PrLocator>,
+        project: Option<String>,
+        timeout: Option<std::time::Duration>,
+        watch_interval: Option<std::time::Duration>,
+    },
+    ArgoVersionMatrixKnown {
+        promotable: bool,
+        project: Option<String>,
     },
     AwsProfileSelected {
         profile: String,
@@ -284,10 +442,21 @@ pub enum GoalParams {
         target: String,
         tear_down: bool,
     },
+    CredentialsCleared {
+        argo: Option<String>,
+        vault: Option<String>,
+        sso: bool,
+        all: bool,
+    },
     GithubPrFilesKnown {
         repo: String,
-        pull_request: Option<u32>,
-        lookback_duration: Option<std::time::Duration>,
+        pr_locator: PrLocator,
+    },
+    GithubRunsKnown {
+        repo: String,
+        branch: String,
+        workflow: Option<String>,
+        wait: bool,
     },
     InfluxDumpCompleted {
         day: Option<NaiveDate>,
@@ -321,6 +490,7 @@ pub enum GoalParams {
     None,
     PgcliRunning {
         aws_profile: Option<String>,
+        role: Option<DbRole>,
     },
     PortForwardEstablished {
         namespace: Option<String>,
@@ -339,6 +509,31 @@ pub enum GoalParams {
         aws_account: Option<AwsAccount>,
         aws_profile: Option<String>,
     },
+    VaultSecretDiffKnown {
+        path: String,
+        from: u64,
+        to: Option<u64>,
+        aws_profile: Option<String>,
+    },
+    VaultSecretHistoryKnown {
+        path: String,
+        aws_profile: Option<String>,
+    },
+    VaultSecretMatchesKnown {
+        pattern: String,
+        prefix: Option<String>,
+        aws_profile: Option<String>,
+    },
+    VaultSecretPathsKnown {
+        prefix: Option<String>,
+        recursive: bool,
+        aws_profile: Option<String>,
+    },
+    VaultSecretWritten {
+        path: String,
+        write: VaultWrite,
+        aws_profile: Option<String>,
+    },
 }
 
 impl From<&GoalParams> for String {
@@ -1,4 +1,5 @@
 use std::convert::From;
+use clap::ValueEnum;
 
 const WORKCELL_DEV_NAME: &str = "workcell (dev)";
 const WORKCELL_STAGE_NAME: &str = "workcell (stage)";
@@ -52,6 +53,25 @@ impl RdsInstance {
     }
 }
 
+impl RdsInstance {
+    /// Name of the database connection within Vault's database secrets engine
+    pub fn vault_database(&self) -> &str {
+        match self {
+            RdsInstance::WorkcellDev => "workcell-dev",
+            RdsInstance::WorkcellStage => "workcell-stage",
+            RdsInstance::WorkcellProd => "workcell-prod",
+            RdsInstance::EventLogDev => "event-log-dev",
+            RdsInstance::EventLogStage => "event-log-stage",
+            RdsInstance::EventLogProd => "event-log-prod",
+        }
+    }
+
+    /// Name of the Vault database role that issues credentials with the given access level
+    pub fn vault_role(&self, role: &DbRole) -> String {
+        format!("{}-{}", self.vault_database(), role.name())
+    }
+}
+
 impl From<&str> for RdsInstance {
     fn from(rds_name: &str) -> Self {
         match rds_name {
@@ -64,4 +84,19 @@ impl From<&str> for RdsInstance {
             _ => panic!("Unknown RDS name: {rds_name}"),
         }
     }
-}
\ No newline at end of file
+}
+/// Access level of short-lived database credentials issued by Vault
+#[derive(Clone, Debug, PartialEq, Eq, Hash, ValueEnum)]
+pub enum DbRole {
+    Readonly,
+    Readwrite,
+}
+
+impl DbRole {
+    pub fn name(&self) -> &str {
+        match self {
+            DbRole::Readonly => "readonly",
+            DbRole::Readwrite => "readwrite",
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::time::Duration;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use console::{measure_text_width, style, Term};
+use serde_json::Value;
+use crate::models::errors::ArcError;
+
+const MASK: &str = "********";
+
+/// Determines how secret values are presented in the outro of a terminal goal
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SecretDisplay {
+    Masked,
+    Copy,
+    Reveal(Duration),
+}
+
+/// Masks a single secret value. JSON objects (e.g. AWS secret strings) keep their keys visible.
+pub fn mask_value(value: &str) -> String {
+    match serde_json::from_str::<Value>(value) {
+        Ok(Value::Object(map)) => {
+            let fields = map.keys()
+                .map(|k| format!("\"{}\": \"{}\"", k, MASK))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("{{{}}}", fields)
+        },
+        _ => MASK.to_string(),
+    }
+}
+
+/// Masks newline-delimited "key: value" pairs, keeping the keys visible.
+/// Lines that don't start a new field (e.g. the remainder of a multi-line PEM key) are dropped.
+pub fn mask_fields(value: &str) -> String {
+    value.lines()
+        .filter_map(|line| {
+            let (key, _) = line.split_once(": ")?;
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return None;
+            }
+            Some(format!("{}: {}", key, MASK))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Describes the field-level changes between two versions of a secret without revealing any values.
+/// Returns an empty list if both versions contain identical fields.
+pub fn masked_diff(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Vec<String> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| match (before.get(key), after.get(key)) {
+            (None, Some(_)) => Some(style(format!("+ {}: {}", key, MASK)).green().to_string()),
+            (Some(_), None) => Some(style(format!("- {}: {}", key, MASK)).red().to_string()),
+            (Some(old), Some(new)) if old != new => {
+                Some(style(format!("~ {}: {} → {}", key, MASK, MASK)).yellow().to_string())
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Copies a value to the clipboard of the user's terminal using an OSC 52 escape sequence.
+/// Since the terminal emulator performs the copy, this also works over SSH.
+pub fn copy_to_clipboard(value: &str) -> Result<(), ArcError> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return Err(ArcError::UserInputError("Unable to copy to clipboard, stderr is not a terminal".to_string()));
+    }
+
+    let encoded = STANDARD.encode(value.as_bytes());
+    let osc52 = format!("\x1b]52;c;{}\x07", encoded);
+
+    // Terminal multiplexers swallow OSC sequences unless they are wrapped in a passthrough sequence
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;\x1b{}\x1b\\", osc52)
+    } else if std::env::var("TERM").is_ok_and(|t| t.starts_with("screen")) {
+        format!("\x1bP{}\x1b\\", osc52)
+    } else {
+        osc52
+    };
+
+    let mut stderr = std::io::stderr();
+    stderr.write_all(sequence.as_bytes())?;
+    stderr.flush()?;
+    Ok(())
+}
+
+/// Temporarily prints a secret value to the terminal, then clears those lines once the
+/// duration has elapsed (or the user hits Ctrl+C), so that the value doesn't stay in scrollback.
+pub async fn reveal(key: &str, value: &str, duration: Duration) -> Result<(), ArcError> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return Err(ArcError::UserInputError("Unable to reveal secret, stderr is not a terminal".to_string()));
+    }
+
+    let bar = style("│").dim();
+    let mut lines = vec![
+        format!("{}  {}", bar, style(format!("{} (hidden in {}s)", key, duration.as_secs())).yellow()),
+    ];
+    lines.extend(value.lines().map(|line| format!("{}  {}", bar, line)));
+
+    // Long lines wrap, so count the rows that each line actually occupies
+    let width = usize::from(term.size().1).max(1);
+    let rows: usize = lines.iter()
+        .map(|line| measure_text_width(line).max(1).div_ceil(width))
+        .sum();
+
+    for line in &lines {
+        term.write_line(line)?;
+    }
+
+    tokio::select! {
+        _ = tokio::time::sleep(duration) => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+
+    term.clear_last_lines(rows)?;
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use serde_json::Value;
+use crate::models::argo::ArgoCdInstance;
 use crate::models::influx::InfluxInstance;
 use crate::models::rds::RdsInstance;
 use crate::models::errors::ArcError;
@@ -41,6 +42,13 @@ impl State {
         }
     }
 
+    pub(crate) fn get_argo_instance(&self, goal: &Goal) -> Result<&ArgoCdInstance, ArcError> {
+        match self.get(goal)? {
+            TaskResult::ArgoInstance(x) => Ok(x),
+            result => Err(ArcError::invalid_state(goal, "ArgoInstance", result)),
+        }
+    }
+
     pub(crate) fn get_aws_profile_info(&self, goal: &Goal) -> Result<&AwsProfileInfo, ArcError> {
         match self.get(goal)? {
             TaskResult::AwsProfile { profile, .. } => Ok(profile),
@@ -100,6 +108,13 @@ impl State {
         }
     }
 
+    pub(crate) fn get_github_token(&self, goal: &Goal) -> Result<&str, ArcError> {
+        match self.get(goal)? {
+            TaskResult::GithubToken(x) => Ok(x),
+            result => Err(ArcError::invalid_state(goal, "GithubToken", result)),
+        }
+    }
+
     pub(crate) fn get_vault_secret(&self, goal: &Goal) -> Result<String, ArcError> {
         match self.get(goal)? {
             TaskResult::VaultSecret(x) => Ok(x.clone()),
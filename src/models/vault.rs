@@ -1,7 +1,18 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use crate::models::aws_profile::AwsAccount;
+use crate::models::config::{CliConfig, VaultInstanceConfig};
+use crate::models::errors::ArcError;
 
 const VAULT_NON_PROD_NAME: &str = "non-prod";
 const VAULT_PROD_NAME: &str = "prod";
+const VAULT_OIDC_AUTH_NAME: &str = "oidc";
+const VAULT_TOKEN_AUTH_NAME: &str = "token";
+const VAULT_APPROLE_AUTH_NAME: &str = "approle";
+const VAULT_AUTH_METHOD_ENV_VAR: &str = "ARCLI_VAULT_AUTH_METHOD";
+const DEFAULT_KV_MOUNT: &str = "kv-v2";
+const DEFAULT_DATABASE_MOUNT: &str = "database";
 
 pub enum VaultInstance {
     NonProd,
@@ -23,13 +34,15 @@ impl VaultInstance {
         }
     }
 
-    pub fn oidc_namespace(&self) -> Option<String> {
+    /// Namespace in which users authenticate and tokens are issued
+    pub fn auth_namespace(&self) -> Option<String> {
         match self {
             VaultInstance::NonProd => Some("admin".to_string()),
             VaultInstance::Prod => Some("admin".to_string()),
         }
     }
 
+    /// Default namespace containing the secrets for an AWS account, if one is known
     pub fn secrets_namespace(&self, account: &AwsAccount) -> Option<String> {
         match self {
             VaultInstance::NonProd => {
@@ -48,11 +61,14 @@ impl VaultInstance {
         }
     }
 
-    pub fn oidc_role(&self) -> Option<String> {
+    pub fn all() -> Vec<VaultInstance> {
+        vec![VaultInstance::NonProd, VaultInstance::Prod]
+    }
+
+    pub fn oidc_role(&self) -> &str {
         match self {
-            //TODO these should be configurable
-            VaultInstance::NonProd => Some("arc-backend-developer".to_string()),
-            VaultInstance::Prod => Some("arc-backend-developer".to_string()),
+            VaultInstance::NonProd => "arc-backend-developer",
+            VaultInstance::Prod => "arc-backend-developer",
         }
     }
 }
@@ -66,3 +82,198 @@ impl From<&str> for VaultInstance {
         }
     }
 }
+
+/// How the CLI authenticates with Vault
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VaultAuthMethod {
+    /// Interactive browser login (the default)
+    Oidc,
+    /// An existing token, read from the VAULT_TOKEN environment variable
+    Token,
+    /// Machine login, with credentials read from the VAULT_ROLE_ID and VAULT_SECRET_ID environment variables
+    AppRole,
+}
+
+impl VaultAuthMethod {
+    /// Default mount path of the auth method
+    pub fn default_mount(&self) -> &str {
+        match self {
+            VaultAuthMethod::Oidc => "oidc",
+            VaultAuthMethod::Token => "token",
+            VaultAuthMethod::AppRole => "approle",
+        }
+    }
+}
+
+impl TryFrom<&str> for VaultAuthMethod {
+    type Error = ArcError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name {
+            VAULT_OIDC_AUTH_NAME => Ok(VaultAuthMethod::Oidc),
+            VAULT_TOKEN_AUTH_NAME => Ok(VaultAuthMethod::Token),
+            VAULT_APPROLE_AUTH_NAME => Ok(VaultAuthMethod::AppRole),
+            _ => Err(ArcError::invalid_config_error(
+                format!("Unknown Vault auth method '{}', expected one of: oidc, token, approle", name)
+            )),
+        }
+    }
+}
+
+/// Settings for connecting to a Vault instance, combining the built-in defaults with any overrides
+/// from the `[vault.<instance>]` section of the config file
+#[derive(Debug)]
+pub struct VaultSettings {
+    pub address: String,
+    pub auth_method: VaultAuthMethod,
+    pub auth_mount: String,
+    pub auth_namespace: Option<String>,
+    pub oidc_role: String,
+    pub kv_mount: String,
+    pub database_mount: String,
+    namespaces: HashMap<String, String>,
+}
+
+impl VaultSettings {
+    pub fn new(instance: &VaultInstance, config: &CliConfig) -> Result<Self, ArcError> {
+        let overrides = config.vault.get(instance.name());
+        let setting = |f: fn(&VaultInstanceConfig) -> &Option<String>| overrides.and_then(|o| f(o).clone());
+
+        // The environment takes precedence so that CI can switch auth methods without a config file
+        let auth_method = match std::env::var(VAULT_AUTH_METHOD_ENV_VAR) {
+            Ok(name) => VaultAuthMethod::try_from(name.as_str())?,
+            Err(_) => overrides.and_then(|o| o.auth_method).unwrap_or(VaultAuthMethod::Oidc),
+        };
+
+        Ok(Self {
+            address: setting(|o| &o.address).unwrap_or_else(|| instance.address().to_string()),
+            auth_method,
+            auth_mount: setting(|o| &o.auth_mount).unwrap_or_else(|| auth_method.default_mount().to_string()),
+            auth_namespace: setting(|o| &o.auth_namespace).or_else(|| instance.auth_namespace()),
+            oidc_role: setting(|o| &o.oidc_role).unwrap_or_else(|| instance.oidc_role().to_string()),
+            kv_mount: setting(|o| &o.kv_mount).unwrap_or_else(|| DEFAULT_KV_MOUNT.to_string()),
+            database_mount: setting(|o| &o.database_mount).unwrap_or_else(|| DEFAULT_DATABASE_MOUNT.to_string()),
+            namespaces: overrides.map(|o| o.namespaces.clone()).unwrap_or_default(),
+        })
+    }
+
+    /// Returns the namespace containing the secrets for an AWS account. Accounts without a default
+    /// namespace must be configured explicitly, rather than silently falling back to the root namespace.
+    pub fn secrets_namespace(&self, instance: &VaultInstance, account: &AwsAccount) -> Result<String, ArcError> {
+        self.namespaces.get(account.name())
+            .cloned()
+            .or_else(|| instance.secrets_namespace(account))
+            .ok_or_else(|| ArcError::invalid_config_error(format!(
+                "No Vault namespace is configured for AWS account '{}', please set vault.{}.namespaces.{}",
+                account.name(),
+                instance.name(),
+                account.name(),
+            )))
+    }
+}
+
+/// A single version of a KV v2 secret, along with the version number needed for check-and-set
+#[derive(Debug)]
+pub struct VaultSecretVersion {
+    pub data: BTreeMap<String, String>,
+    pub version: u64,
+}
+
+/// Metadata describing a single version of a KV v2 secret
+#[derive(Debug)]
+pub struct VaultSecretVersionInfo {
+    pub version: u64,
+    pub created_time: DateTime<Utc>,
+    pub deletion_time: Option<DateTime<Utc>>,
+    pub destroyed: bool,
+}
+
+/// A modification to a KV v2 secret. Fields without a value will be prompted for.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum VaultWrite {
+    Set(Vec<(String, Option<String>)>),
+    Patch(Vec<(String, Option<String>)>),
+    Delete,
+    Rollback(u64),
+}
+
+/// Response from a Vault secrets engine that issues leased (i.e. dynamic) credentials
+#[derive(Debug, Deserialize)]
+pub struct VaultLeaseResponse<T> {
+    pub lease_id: String,
+    pub lease_duration: u64,
+    pub data: T,
+}
+
+/// Short-lived credentials issued by Vault's database secrets engine
+#[derive(Debug, Deserialize)]
+pub struct VaultDatabaseCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// A lease that was issued to this CLI and has not yet been revoked
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackedLease {
+    pub lease_id: String,
+    pub vault_instance: String,
+    pub namespace: Option<String>,
+    pub expires_at: DateTime<Utc>,
+
+    /// Process that the lease was issued to, which is missing for leases tracked by older versions
+    #[serde(default)]
+    pub owner: Option<LeaseOwner>,
+}
+
+/// Host and process ID of a session that holds a lease
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LeaseOwner {
+    pub hostname: String,
+    pub pid: u32,
+}
+
+impl LeaseOwner {
+    pub fn current() -> Self {
+        LeaseOwner { hostname: hostname(), pid: std::process::id() }
+    }
+
+    /// Whether the owning process still exists. Processes on other hosts (e.g. sharing a home
+    /// directory) can't be checked, so they're assumed to still be running.
+    pub fn is_running(&self) -> bool {
+        if self.hostname != hostname() {
+            return true;
+        }
+        if self.pid == std::process::id() {
+            return true;
+        }
+        is_process_running(self.pid)
+    }
+}
+
+fn hostname() -> String {
+    std::process::Command::new("hostname")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(unix)]
+fn is_process_running(pid: u32) -> bool {
+    // Signal 0 only checks whether the process exists
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(windows)]
+fn is_process_running(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+}
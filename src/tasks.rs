@@ -16,16 +16,32 @@ pub mod select_rds_instance;
 pub mod logging;
 pub mod get_argo_app_statuses;
 pub mod get_github_pr_files;
+pub mod write_vault_secret;
+pub mod get_vault_secret_history;
+pub mod diff_vault_secret;
+pub mod list_vault_secrets;
+pub mod find_vault_secrets;
+pub mod get_auth_status;
+pub mod logout;
+pub mod select_argo_instance;
+pub mod run_argo_operation;
+pub mod get_argo_app_details;
+pub mod get_argo_version_matrix;
+pub mod get_argo_app_history;
+pub mod get_github_token;
+pub mod promote_apps;
+pub mod get_github_runs;
 
 use async_trait::async_trait;
 use cliclack::progress_bar;
 use std::collections::HashMap;
 use crate::{GoalStatus, State};
 use crate::models::influx::InfluxInstance;
-use crate::models::argo::AppInfo;
+use crate::models::argo::{AppInfo, AppVersions, ArgoCdInstance, ArgoDeployment, ArgoResourceDetail};
 use crate::models::aws_profile::AwsProfileInfo;
-use crate::models::github::GithubPrFile;
+use crate::models::github::{GithubPrFile, GithubWorkflowRun};
 use crate::models::rds::RdsInstance;
+use crate::models::vault::VaultSecretVersionInfo;
 use crate::models::config::CliConfig;
 use crate::models::errors::ArcError;
 use crate::models::goals::GoalParams;
@@ -49,11 +65,21 @@ pub trait Task: Send + Sync {
 #[derive(Debug)]
 pub enum TaskResult {
     ActuatorService(ActuatorService),
-    ArgoAppStatuses(HashMap<String, AppInfo>),
+    AppsPromoted(u64),
+    ArgoAppDetails(Vec<ArgoResourceDetail>),
+    ArgoAppHistory(Vec<ArgoDeployment>),
+    ArgoAppStatuses(HashMap<ArgoCdInstance, HashMap<String, AppInfo>>),
+    ArgoInstance(ArgoCdInstance),
+    ArgoOperationCompleted,
+    ArgoVersionMatrix(Vec<AppVersions>),
+    AuthStatus,
     AwsProfile{ profile: AwsProfileInfo, updated: bool },
     AwsSecret(String),
     BazelProcess(BazelProcessInfo),
+    CredentialsCleared,
     GithubPrFiles(Vec<GithubPrFile>),
+    GithubRuns(Vec<GithubWorkflowRun>),
+    GithubToken(String),
     InfluxCommand,
     InfluxInstance(InfluxInstance),
     InfluxDumpCompleted,
@@ -61,11 +87,17 @@ pub enum TaskResult {
     LogLevel,
     Organization(Organization),
     PgcliCommand(String),
+    PgcliSessionEnded,
     PortForward(Vec<PortForwardInfo>),
     RdsInstance(RdsInstance),
     SsoSessionValid,
     TabCompletionsCreated,
     VaultSecret(String),
+    VaultSecretDiff(Vec<String>),
+    VaultSecretHistory(Vec<VaultSecretVersionInfo>),
+    VaultSecretMatches(Vec<String>),
+    VaultSecretPaths(Vec<String>),
+    VaultSecretWritten(u64),
 }
 
 impl TaskResult {
@@ -109,3 +141,21 @@ pub async fn sleep_indicator(seconds: u64, start_msg: &str, end_msg: &str) {
 
     progress.stop(end_msg);
 }
+
+/// Requires the user to type out the name of a production target before a change is applied to it
+pub fn confirm_production_change(target: &str) -> Result<(), ArcError> {
+    cliclack::log::warning(format!("This change targets {}", console::style("PRODUCTION").red().bold()))?;
+
+    let expected = target.to_string();
+    let _: String = cliclack::input(format!("Type '{}' to confirm", target))
+        .validate(move |input: &String| {
+            if *input == expected {
+                Ok(())
+            } else {
+                Err("Input does not match")
+            }
+        })
+        .interact()?;
+
+    Ok(())
+}
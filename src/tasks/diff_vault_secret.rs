@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use cliclack::intro;
+use crate::tasks::{Task, TaskResult};
+use crate::clients::vault_client::VaultClient;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::secret_display::masked_diff;
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+
+#[derive(Debug)]
+pub struct DiffVaultSecretTask;
+
+#[async_trait]
+impl Task for DiffVaultSecretTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Diff Vault Secret")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::VaultSecretDiffKnown { path, from, to, aws_profile } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::VaultSecretDiffKnown, params));
+        };
+
+        // Infer the AWS account, and therefore the Vault instance, from an AWS profile
+        let profile_goal = Goal::aws_profile_selected(aws_profile.clone());
+        if !state.contains(&profile_goal) {
+            return Ok(GoalStatus::Needs(profile_goal));
+        }
+        let profile_info = state.get_aws_profile_info(&profile_goal)?;
+        let client = VaultClient::new(&profile_info.account, config)?;
+
+        // A missing version is either out of range, deleted, or destroyed
+        let missing_version = |version: Option<u64>| {
+            let version = version.map_or("Latest version".to_string(), |v| format!("Version {}", v));
+            ArcError::UserInputError(format!("{} of Vault secret '{}' does not exist or has been deleted", version, path))
+        };
+        let before = client.guarded_read_secret_version(path, Some(*from)).await?
+            .ok_or_else(|| missing_version(Some(*from)))?;
+        let after = client.guarded_read_secret_version(path, *to).await?
+            .ok_or_else(|| missing_version(*to))?;
+
+        let changes = masked_diff(&before.data, &after.data);
+        let summary = if changes.is_empty() {
+            "No changes".to_string()
+        } else {
+            changes.join("\n")
+        };
+
+        let key = format!("{} (version {} → {})", path, before.version, after.version);
+        let outro_text = OutroText::multi(key, summary);
+        Ok(GoalStatus::Completed(TaskResult::VaultSecretDiff(changes), outro_text))
+    }
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use cliclack::{intro, log, progress_bar};
+use crate::tasks::{Task, TaskResult};
+use crate::tasks::list_vault_secrets::normalize_prefix;
+use crate::clients::vault_client::VaultClient;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+
+#[derive(Debug)]
+pub struct FindVaultSecretsTask;
+
+#[async_trait]
+impl Task for FindVaultSecretsTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Find Vault Secrets")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::VaultSecretMatchesKnown { pattern, prefix, aws_profile } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::VaultSecretMatchesKnown, params));
+        };
+
+        // Infer the AWS account, and therefore the Vault instance, from an AWS profile
+        let profile_goal = Goal::aws_profile_selected(aws_profile.clone());
+        if !state.contains(&profile_goal) {
+            return Ok(GoalStatus::Needs(profile_goal));
+        }
+        let profile_info = state.get_aws_profile_info(&profile_goal)?;
+        let client = VaultClient::new(&profile_info.account, config)?;
+
+        let prefix = normalize_prefix(prefix.as_deref());
+        let paths = client.guarded_list_tree(&prefix).await?;
+        let lowercase_pattern = pattern.to_lowercase();
+
+        // Field names can only be discovered by reading each secret, so show progress
+        let progress = progress_bar(paths.len() as u64);
+        progress.start("Searching secrets...");
+
+        let mut matches = Vec::new();
+        let mut lines = Vec::new();
+        let mut unreadable = 0;
+        for path in &paths {
+            // Policies often allow listing secrets that can't be read, which shouldn't end the search
+            let fields = match client.guarded_list_fields(path).await {
+                Ok(fields) => fields,
+                Err(ArcError::VaultPermissionDenied(_)) => {
+                    unreadable += 1;
+                    Vec::new()
+                },
+                Err(e) => return Err(e),
+            };
+            let matching_fields: Vec<String> = fields.into_iter()
+                .filter(|field| field.to_lowercase().contains(&lowercase_pattern))
+                .collect();
+            progress.inc(1);
+
+            if !matching_fields.is_empty() {
+                lines.push(format!("{} ({})", path, matching_fields.join(", ")));
+                matches.push(path.clone());
+            } else if path.to_lowercase().contains(&lowercase_pattern) {
+                lines.push(path.clone());
+                matches.push(path.clone());
+            }
+        }
+        progress.stop(format!("Searched {} secrets", paths.len()));
+        if unreadable > 0 {
+            log::warning(format!("Skipped the fields of {} secrets that you aren't allowed to read", unreadable))?;
+        }
+
+        if matches.is_empty() {
+            return Err(ArcError::UserInputError(format!("No secrets found matching '{}'", pattern)));
+        }
+
+        let key = format!("{} matching secrets", matches.len());
+        let outro_text = OutroText::multi(key, lines.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::VaultSecretMatches(matches), outro_text))
+    }
+}
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use cliclack::{intro, spinner};
+use crate::clients::argo_client::ArgoClient;
+use crate::models::argo::ArgoResourceDetail;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+#[derive(Debug)]
+pub struct GetArgoAppDetailsTask;
+
+#[async_trait]
+impl Task for GetArgoAppDetailsTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Get ArgoCD app details")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::ArgoAppDetailsKnown { app, env } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoAppDetailsKnown, params));
+        };
+
+        let instance_goal = Goal::argo_instance_selected(env.clone());
+        if !state.contains(&instance_goal) {
+            return Ok(GoalStatus::Needs(instance_goal));
+        }
+        let instance = *state.get_argo_instance(&instance_goal)?;
+        let client = ArgoClient::new(instance, &config.argo)?;
+
+        let progress = spinner();
+        progress.start(format!("Fetching resources of {}...", app));
+        let application = client.fetch_app(app).await?;
+        let tree = client.fetch_resource_tree(app).await?;
+
+        // Live manifests provide replica counts and conditions, but aren't essential to show the tree
+        let mut details = Vec::new();
+        for node in tree.nodes.iter().filter(|node| node.is_workload()) {
+            let manifest = client.fetch_live_manifest(app, node).await.ok();
+            details.push(ArgoResourceDetail::new(node, manifest.as_ref()));
+        }
+        details.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+        progress.stop(format!("Fetched {} workloads of {}", details.len(), app));
+
+        let mut lines = Vec::new();
+        let health = application.health();
+        lines.push(format!(
+            "Sync: {}, Health: {}{}",
+            application.sync_status(),
+            health.map(|h| h.status.as_str()).unwrap_or("Unknown"),
+            health.and_then(|h| h.message.as_deref()).map(|m| format!(" ({})", m)).unwrap_or_default(),
+        ));
+        for detail in &details {
+            lines.push(String::new());
+            lines.push(render_detail(detail));
+        }
+
+        let key = format!("ArgoCD Application {} ({})", app, instance.name());
+        let outro_text = OutroText::multi(key, lines.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::ArgoAppDetails(details), outro_text))
+    }
+}
+
+fn render_detail(detail: &ArgoResourceDetail) -> String {
+    let mut lines = vec![format!(
+        "{:<11} {:<35} {:<12} {}",
+        detail.kind,
+        detail.name,
+        detail.health,
+        detail.ready.as_deref().unwrap_or(""),
+    )];
+    if let Some(message) = &detail.health_message {
+        lines.push(format!("    {}", message));
+    }
+    for image in &detail.images {
+        lines.push(format!("    image: {}", image));
+    }
+    for condition in &detail.conditions {
+        lines.push(format!("    ⚠️ {}", condition));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use cliclack::{intro, spinner};
+use crate::clients::argo_client::ArgoClient;
+use crate::clients::github_client::{GithubClient, GITOPS_REPO};
+use crate::models::argo::{ArgoCdInstance, ArgoDeployment, VersionBump};
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::github::GithubCommitDetails;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::get_argo_app_statuses::app_chart_dir;
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+// Each deployment needs a few GitHub requests, so only recent history is shown
+const HISTORY_LIMIT: usize = 10;
+
+// Number of commits to the app's chart, before a deployed revision, searched for its version bump
+const COMMIT_LOOKBACK: usize = 10;
+
+#[derive(Debug)]
+pub struct GetArgoAppHistoryTask;
+
+#[async_trait]
+impl Task for GetArgoAppHistoryTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Get ArgoCD app history")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::ArgoAppHistoryKnown { app, env } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoAppHistoryKnown, params));
+        };
+
+        let instance_goal = Goal::argo_instance_selected(env.clone());
+        if !state.contains(&instance_goal) {
+            return Ok(GoalStatus::Needs(instance_goal));
+        }
+        let token_goal = Goal::github_token_known();
+        if !state.contains(&token_goal) {
+            return Ok(GoalStatus::Needs(token_goal));
+        }
+
+        let instance = *state.get_argo_instance(&instance_goal)?;
+        let argo_client = ArgoClient::new(instance, &config.argo)?;
+        let github_client = GithubClient::new(state.get_github_token(&token_goal)?);
+
+        let application = argo_client.fetch_app(app).await?;
+        let project = application.project()
+            .ok_or_else(|| ArcError::UserInputError(format!("ArgoCD application '{}' has no project", app)))?;
+        let chart_dir = app_chart_dir(project, app);
+        let version_file = application.version_file();
+        let history: Vec<_> = application.history().into_iter().take(HISTORY_LIMIT).collect();
+        if history.is_empty() {
+            return Err(ArcError::UserInputError(format!("ArgoCD application '{}' has no deployment history", app)));
+        }
+
+        let progress = spinner();
+        progress.start("Looking up the gitops change behind each deployment...");
+
+        // Consecutive deployments often share commits, so each is only fetched once
+        let mut commits = HashMap::new();
+        let mut deployments = Vec::new();
+        for entry in history {
+            let version_bump = match entry.revision.as_deref() {
+                Some(revision) => find_version_bump(&github_client, &chart_dir, version_file.as_deref(), instance, revision, &mut commits).await?,
+                None => None,
+            };
+            deployments.push(ArgoDeployment {
+                id: entry.id,
+                deployed_at: entry.deployed_at.clone(),
+                revision: entry.revision.clone(),
+                version_bump,
+            });
+        }
+        progress.stop(format!("Deployment history of {}", app));
+
+        let mut rows = vec![format!("{:<6} {:<22} {:<9} {}", "ID", "Deployed At", "Revision", "Image Tag")];
+        for (i, deployment) in deployments.iter().enumerate() {
+            rows.push(String::new());
+            rows.push(render_deployment(deployment, i == 0));
+        }
+
+        let key = format!("ArgoCD Application {} history ({})", app, instance.name());
+        let outro_text = OutroText::multi(key, rows.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::ArgoAppHistory(deployments), outro_text))
+    }
+}
+
+/// Finds the most recent commit, as of a deployed revision, that changed the app's image tag in the
+/// instance's environment, along with the PR that merged it. If the app's version file is known,
+/// only changes to it count, otherwise changes to any region of the environment do.
+async fn find_version_bump(
+    client: &GithubClient,
+    chart_dir: &str,
+    version_file: Option<&str>,
+    instance: ArgoCdInstance,
+    revision: &str,
+    commits: &mut HashMap<String, GithubCommitDetails>,
+) -> Result<Option<VersionBump>, ArcError> {
+    let chart_prefix = format!("{}/", chart_dir);
+    let version_file_suffix = format!("/{}/version.yaml", instance.name());
+    let is_version_file = |filename: &str| match version_file {
+        Some(version_file) => filename == version_file,
+        None => filename.starts_with(&chart_prefix) && filename.ends_with(&version_file_suffix),
+    };
+
+    for commit in client.fetch_path_commits(GITOPS_REPO, revision, chart_dir, COMMIT_LOOKBACK).await? {
+        if !commits.contains_key(&commit.sha) {
+            let details = client.fetch_commit(GITOPS_REPO, &commit.sha).await?;
+            commits.insert(commit.sha.clone(), details);
+        }
+
+        // Commits to the chart may only change other environments, or other settings than the version
+        let changed_file = commits[&commit.sha].files.iter()
+            .filter(|file| is_version_file(&file.filename))
+            .find(|file| file.added_tag().is_some());
+        let Some(changed_file) = changed_file else {
+            continue;
+        };
+
+        let pull_request = client.fetch_commit_prs(GITOPS_REPO, &commit.sha).await?
+            .into_iter()
+            .find(|pr| pr.merged_at.is_some());
+
+        return Ok(Some(VersionBump {
+            commit: commit.sha,
+            previous_tag: changed_file.removed_tag(),
+            tag: changed_file.added_tag().unwrap_or_default(),
+            pull_request,
+        }));
+    }
+
+    Ok(None)
+}
+
+fn render_deployment(deployment: &ArgoDeployment, is_current: bool) -> String {
+    let short_revision: String = deployment.revision.as_deref().unwrap_or("unknown").chars().take(7).collect();
+    let image_tag = match &deployment.version_bump {
+        Some(VersionBump { previous_tag: Some(previous_tag), tag, .. }) => format!("{} → {}", previous_tag, tag),
+        Some(VersionBump { tag, .. }) => tag.clone(),
+        None => "unknown".to_string(),
+    };
+    let current_marker = if is_current { " (current)" } else { "" };
+
+    let mut lines = vec![format!(
+        "{:<6} {:<22} {:<9} {}{}",
+        deployment.id,
+        deployment.deployed_at.as_deref().unwrap_or("unknown"),
+        short_revision,
+        image_tag,
+        current_marker,
+    )];
+    match &deployment.version_bump {
+        Some(VersionBump { pull_request: Some(pr), .. }) => {
+            lines.push(format!("    #{} {} (by @{})", pr.number, pr.title, pr.user.login));
+        },
+        Some(VersionBump { commit, .. }) => {
+            let short_commit: String = commit.chars().take(7).collect();
+            lines.push(format!("    commit {} (no merged PR found)", short_commit));
+        },
+        None => lines.push(format!("    no image tag change found in the last {} commits", COMMIT_LOOKBACK)),
+    }
+    lines.join("\n")
+}
@@ -1,18 +1,29 @@
-use std::collections::HashMap;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Local};
+use console::{style, Term};
 use cliclack::{intro, multi_progress, spinner, ProgressBar};
 use async_trait::async_trait;
+use tokio::task::JoinSet;
 use crate::{GoalStatus, OutroText};
 use crate::models::config::CliConfig;
 use crate::models::errors::ArcError;
 use crate::models::goals::{Goal, GoalParams, GoalType};
 use crate::models::state::State;
 use crate::tasks::{Task, TaskResult};
-use crate::models::argo::{AppInfo, ArgoCdInstance};
+use crate::models::argo::{AppField, AppInfo, ArgoCdInstance};
 use crate::clients::argo_client::ArgoClient;
-use crate::models::aws_profile::AwsProfileInfo;
+use crate::clients::github_client::GITOPS_REPO;
 use crate::models::github::GithubPrFile;
 
+// Number of entries kept in the change log below the watch table
+const CHANGE_LOG_SIZE: usize = 15;
+const HIGHLIGHT_SECS: i64 = 60;
+
+// Number of polls in a row that may fail, e.g. due to network blips, before waiting for a PR's apps gives up
+const MAX_POLL_FAILURES: u32 = 5;
+
 #[derive(Debug)]
 pub struct GetArgoAppStatusesTask;
 
@@ -26,21 +37,13 @@ impl Task for GetArgoAppStatusesTask {
     async fn execute(
         &self,
         params: &GoalParams,
-        _config: &CliConfig,
+        config: &CliConfig,
         state: &State
     ) -> Result<GoalStatus, ArcError> {
-        // Determine which ArgoCD instance to query and optionally which apps to filter
-        let (argo_instance, target_versions) = match params {
-            GoalParams::ArgoStatusesKnown { pull_request: Some(pr) } => {
-                // Construct params for GitHub goal
-                let repo = "services-gitops".to_string();
-                let (pull_request, lookback_duration) =  if *pr == 0u32 {
-                    // We use a sentinel value of zero when user specifies '-pr' option without a value
-                    (None, Some(Duration::from_mins(10)))
-                } else {
-                    (Some(*pr), None)
-                };
-                let github_goal = Goal::github_pr_files_known(repo, pull_request, lookback_duration);
+        // Determine which ArgoCD instance to query, or wait for the apps of a PR across all of its instances
+        let argo_instance = match params {
+            GoalParams::ArgoStatusesKnown { pull_request: Some(pr_locator), project, timeout, .. } => {
+                let github_goal = Goal::github_pr_files_known(GITOPS_REPO.to_string(), pr_locator.clone());
 
                 // If we haven't obtained GitHub PR files yet, we need to wait for that goal to complete
                 if !state.contains(&github_goal) {
@@ -49,193 +52,364 @@ impl Task for GetArgoAppStatusesTask {
 
                 // Retrieve GitHub PR's changed files from state
                 let pr_files = state.get_github_pr_files(&github_goal)?;
-                parse_github_pr_files(pr_files).await?
+                let projects = config.argo.projects(project.as_deref());
+                let target_versions = parse_github_pr_files(pr_files, &projects)?;
+                return wait_for_pr_apps(target_versions, config, &projects, *timeout).await;
             },
-            GoalParams::ArgoStatusesKnown { pull_request: None } => {
-                if let Some(profile) = AwsProfileInfo::current().await {
-                    // An AWS profile is currently active, so use it to infer ArgoCD instance
-                    (ArgoCdInstance::from(&profile), HashMap::new())
-                } else {
-                    // No AWS profile is currently active, prompt user to select ArgoCD instance
-                    let argo_instance = prompt_for_argo_instance()?;
-                    (argo_instance, HashMap::new())
+            GoalParams::ArgoStatusesKnown { pull_request: None, .. } => {
+                // The instance is inferred from the active AWS profile, or prompted for if there isn't one
+                let instance_goal = Goal::argo_instance_selected(None);
+                if !state.contains(&instance_goal) {
+                    return Ok(GoalStatus::Needs(instance_goal));
                 }
+                *state.get_argo_instance(&instance_goal)?
             },
             _ => return Err(ArcError::invalid_goal_params(GoalType::ArgoStatusKnown, params)),
         };
 
         // Create session guard to handle token renewal
-        let argo_client = ArgoClient::new(argo_instance.clone())?;
+        let argo_client = ArgoClient::new(argo_instance, &config.argo)?;
 
         // Retrieve the initial status of all apps
-        let apps = argo_client.fetch_apps("arc").await?;
-
-        let mut apps_to_monitor: Vec<&str> = if target_versions.is_empty() {
-            // Monitor all apps
-            apps.keys().map(|s| s.as_str()).collect()
-        } else {
-            // Monitor only those apps that are modified in the given PR
-            target_versions.keys().map(|s| s.as_str()).collect()
+        let GoalParams::ArgoStatusesKnown { project, watch_interval, .. } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoStatusKnown, params));
         };
-        apps_to_monitor.sort();
-
-        if target_versions.is_empty() {
-            // Just show a single snapshot of the current status as a table
-            let mut rows = Vec::new();
-            rows.push(AppInfo::header());
-            rows.push("-".repeat(105));
-            for name in apps_to_monitor.iter() {
-                let app_status = apps.get(*name)
-                    .map(|a| a.to_string())
-                    .unwrap_or_else(|| format!("❓ {:<30} {:<23} {:<40}", name, "-", "-"));
-                rows.push(app_status);
+        let projects = config.argo.projects(project.as_deref());
+        let apps = argo_client.fetch_apps(&projects).await?;
+
+        if let Some(interval) = watch_interval {
+            return watch_apps(&argo_client, argo_instance, &projects, apps, *interval).await;
+        }
+
+        // Just show a single snapshot of the current status as a table
+        let mut app_names: Vec<&String> = apps.keys().collect();
+        app_names.sort();
+
+        let mut rows = Vec::new();
+        rows.push(AppInfo::header());
+        rows.push("-".repeat(118));
+        for name in app_names {
+            rows.push(apps[name].to_string());
+        }
+
+        let status_msg = rows.join("\n");
+        let prompt = format!("ArgoCD Application Status ({})", argo_instance.name());
+        let outro_text = OutroText::multi(prompt, status_msg);
+
+        let statuses = HashMap::from([(argo_instance, apps)]);
+        Ok(GoalStatus::Completed(TaskResult::ArgoAppStatuses(statuses), outro_text))
+    }
+}
+
+/// Continually updates the status of each app changed by a PR, in every ArgoCD instance that it
+/// deploys to, until all of them are synced to their target versions, have failed or timed out
+async fn wait_for_pr_apps(
+    target_versions: HashMap<ArgoCdInstance, HashMap<String, String>>,
+    config: &CliConfig,
+    projects: &[String],
+    timeout: Option<Duration>,
+) -> Result<GoalStatus, ArcError> {
+    let mut instances: Vec<ArgoCdInstance> = target_versions.keys().copied().collect();
+    instances.sort_by_key(|instance| ArgoCdInstance::all().iter().position(|i| i == instance));
+
+    // Fetch the initial statuses one instance at a time, since each may need an interactive login
+    let mut clients = HashMap::new();
+    let mut apps_by_instance = HashMap::new();
+    for &instance in &instances {
+        let client = Arc::new(ArgoClient::new(instance, &config.argo)?);
+        apps_by_instance.insert(instance, client.fetch_apps(projects).await?);
+        clients.insert(instance, client);
+    }
+
+    let instance_names: Vec<&str> = instances.iter().map(|instance| instance.name()).collect();
+    let multi = multi_progress(format!("Waiting for ArgoCD ({}) applications to sync...", instance_names.join(", ")));
+
+    // Create a progress spinner for each app in each instance
+    let mut apps_to_monitor: Vec<(ArgoCdInstance, &str)> = instances.iter()
+        .flat_map(|instance| target_versions[instance].keys().map(|name| (*instance, name.as_str())))
+        .collect();
+    apps_to_monitor.sort_by_key(|(instance, name)| (ArgoCdInstance::all().iter().position(|i| i == instance), *name));
+    let mut spinners: Vec<((ArgoCdInstance, &str), ProgressBar)> = apps_to_monitor.iter()
+        .map(|&key| (key, multi.add(spinner())))
+        .collect();
+
+    // Wait until all spinners have been added before starting any of them, just to be safe
+    for ((instance, name), spinner) in &spinners {
+        match apps_by_instance[instance].get(*name) {
+            Some(app) => {
+                spinner.start(format!(" {:<6} {}", instance.name(), app.minimal_text(false)));
+            },
+            None => {
+                spinner.start(format!(" {:<6} {:<30} {:<23} {:<40}", instance.name(), name, "-", "-"));
             }
+        }
+    }
 
-            let status_msg = rows.join("\n");
-            let prompt = format!("ArgoCD Application Status ({})", argo_instance.name());
-            let outro_text = OutroText::multi(prompt, status_msg);
-
-            Ok(GoalStatus::Completed(TaskResult::ArgoAppStatuses(apps), outro_text))
-        } else {
-            // Continually update the app statuses until all apps are synced
-            let multi = multi_progress(format!("Waiting for ArgoCD ({}) applications to sync...", argo_instance.name()));
-
-            // Create a progress spinner for each app
-            let mut spinners: HashMap<&str, ProgressBar> = apps_to_monitor.iter()
-                .map(|&name| {
-                    let item = multi.add(spinner());
-                    (name, item)
-                })
-                .collect();
-
-            // Wait until all spinners have been added before starting any of them, just to be safe
-            for (&name, spinner) in &spinners {
-                match apps.get(name) {
-                    Some(app) => {
-                        spinner.start(format!(" {}", app.minimal_text(false)));
-                    },
-                    None => {
-                        spinner.start(format!(" {:<30} {:<23} {:<40}", name, "-", "-"));
-                    }
-                }
+    // Operations that had already started before we began waiting belong to previous deploys
+    let initial_operations: HashMap<(ArgoCdInstance, String), Option<String>> = apps_by_instance.iter()
+        .flat_map(|(instance, apps)| apps.iter().map(|(name, app)| ((*instance, name.clone()), app.started_at.clone())))
+        .collect();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut failures = Vec::new();
+    let mut poll_failures: HashMap<ArgoCdInstance, u32> = HashMap::new();
+
+    // Loop until all apps are synced or failed and corresponding spinners are stopped
+    spinners = update_progress(&apps_by_instance, &target_versions, &initial_operations, spinners, &mut failures);
+    while !spinners.is_empty() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            for ((instance, name), spinner) in spinners.drain(..) {
+                spinner.error(format!("{:<6} {:<30} timed out waiting to sync", instance.name(), name));
+                failures.push(format!("{} in {} timed out", name, instance.name()));
             }
+            break;
+        }
 
-            // Loop until all apps are synced and corresponding spinners are stopped
-            spinners = update_progress(&apps, &target_versions, spinners)?;
-            while !spinners.is_empty() {
-                tokio::time::sleep(Duration::from_secs(2)).await;
-                let apps = argo_client.fetch_apps("arc").await?;
-                spinners = update_progress(&apps, &target_versions, spinners)?;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        // Only poll the instances that still have apps being waited on, all at once
+        let mut requests = JoinSet::new();
+        let mut pending_instances: Vec<ArgoCdInstance> = spinners.iter().map(|((instance, _), _)| *instance).collect();
+        pending_instances.dedup();
+        for instance in pending_instances {
+            let client = Arc::clone(&clients[&instance]);
+            let projects = projects.to_vec();
+            requests.spawn(async move { (instance, client.fetch_apps(&projects).await) });
+        }
+        while let Some(result) = requests.join_next().await {
+            let (instance, apps) = result
+                .map_err(|e| ArcError::CommandExecutionError(format!("ArgoCD request failed: {}", e)))?;
+
+            // Keep the previous statuses after a failed poll, unless the instance keeps failing
+            match apps {
+                Ok(apps) => {
+                    poll_failures.remove(&instance);
+                    apps_by_instance.insert(instance, apps);
+                },
+                Err(e) => {
+                    let count = poll_failures.entry(instance).or_default();
+                    *count += 1;
+                    if *count >= MAX_POLL_FAILURES {
+                        multi.error(format!("Unable to reach ArgoCD ({})", instance.name()));
+                        return Err(e);
+                    }
+                },
             }
+        }
+        spinners = update_progress(&apps_by_instance, &target_versions, &initial_operations, spinners, &mut failures);
+    }
+
+    multi.stop();
+
+    // Fail the command, so that scripts can gate on the PR's apps being deployed
+    if !failures.is_empty() {
+        return Err(ArcError::CommandExecutionError(format!(
+            "{} of {} apps failed to deploy: {}",
+            failures.len(),
+            apps_to_monitor.len(),
+            failures.join(", ")
+        )));
+    }
+    Ok(GoalStatus::Completed(TaskResult::ArgoAppStatuses(apps_by_instance), OutroText::None))
+}
 
-            multi.stop();
-            Ok(GoalStatus::Completed(TaskResult::ArgoAppStatuses(apps), OutroText::None))
+/// Redraws a table of all apps every interval until interrupted, highlighting recent changes and
+/// keeping a log of them underneath
+async fn watch_apps(
+    argo_client: &ArgoClient,
+    argo_instance: ArgoCdInstance,
+    projects: &[String],
+    mut apps: HashMap<String, AppInfo>,
+    interval: Duration,
+) -> Result<GoalStatus, ArcError> {
+    let term = Term::stderr();
+    let mut highlights: HashMap<String, Vec<(AppField, DateTime<Local>)>> = HashMap::new();
+    let mut change_log: VecDeque<String> = VecDeque::new();
+    let mut last_error: Option<String> = None;
+    let mut drawn_lines = 0;
+
+    loop {
+        // Changed cells stay highlighted for a while, so that changes aren't missed between glances
+        let now = Local::now();
+        for fields in highlights.values_mut() {
+            fields.retain(|(_, changed_at)| now - *changed_at < chrono::Duration::seconds(HIGHLIGHT_SECS));
         }
+
+        let mut lines = vec![
+            format!(
+                "ArgoCD ({}) applications, refreshed at {} every {}s (press Ctrl-C to stop)",
+                argo_instance.name(),
+                now.format("%H:%M:%S"),
+                interval.as_secs()
+            ),
+        ];
+        if let Some(error) = &last_error {
+            lines.push(style(format!("Last refresh failed, showing previous statuses: {}", error)).red().to_string());
+        }
+        lines.push(String::new());
+        lines.push(AppInfo::header());
+        lines.push("-".repeat(118));
+
+        let mut names: Vec<&String> = apps.keys().collect();
+        names.sort();
+        for name in names {
+            let highlighted: Vec<AppField> = highlights.get(name)
+                .map(|fields| fields.iter().map(|(field, _)| *field).collect())
+                .unwrap_or_default();
+            lines.push(apps[name].highlighted_row(&highlighted));
+        }
+
+        lines.push(String::new());
+        lines.push("Changes:".to_string());
+        if change_log.is_empty() {
+            lines.push(style("  none yet").dim().to_string());
+        }
+        lines.extend(change_log.iter().map(|entry| format!("  {}", entry)));
+
+        if drawn_lines > 0 {
+            term.clear_last_lines(drawn_lines)?;
+        }
+        for line in &lines {
+            term.write_line(line)?;
+        }
+        drawn_lines = lines.len();
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {},
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        // A failed refresh, e.g. due to a network blip, shouldn't end a long running watch
+        let latest = match argo_client.fetch_apps(projects).await {
+            Ok(latest) => latest,
+            Err(e) => {
+                last_error = Some(e.to_string());
+                continue;
+            }
+        };
+        last_error = None;
+
+        let now = Local::now();
+        let mut names: Vec<&String> = latest.keys().chain(apps.keys().filter(|name| !latest.contains_key(*name))).collect();
+        names.sort();
+        for name in names {
+            let descriptions = match (apps.get(name), latest.get(name)) {
+                (Some(previous), Some(current)) => {
+                    let changes = current.changes_since(previous);
+                    let fields = highlights.entry(name.clone()).or_default();
+                    fields.extend(changes.iter().map(|(field, _)| (*field, now)));
+                    changes.into_iter().map(|(_, description)| description).collect()
+                },
+                (None, _) => vec!["added".to_string()],
+                (_, None) => vec!["removed".to_string()],
+            };
+            for description in descriptions {
+                change_log.push_front(format!("{} {:<30} {}", now.format("%H:%M:%S"), name, description));
+            }
+        }
+        change_log.truncate(CHANGE_LOG_SIZE);
+        apps = latest;
     }
+
+    let outro_text = OutroText::single("ArgoCD Watch".to_string(), format!("stopped watching {} apps", apps.len()));
+    let statuses = HashMap::from([(argo_instance, apps)]);
+    Ok(GoalStatus::Completed(TaskResult::ArgoAppStatuses(statuses), outro_text))
 }
 
+type AppSpinners<'a> = Vec<((ArgoCdInstance, &'a str), ProgressBar)>;
+
 fn update_progress<'a>(
-    apps: &HashMap<String, AppInfo>,
-    target_versions: &HashMap<String, String>,
-    spinners: HashMap<&'a str, ProgressBar>
-) -> Result<HashMap<&'a str, ProgressBar>, ArcError> {
-    let mut unsynced_app_spinners: HashMap<&str, ProgressBar> = HashMap::new();
-
-    for (name, spinner) in spinners {
-        match apps.get(name) {
+    apps_by_instance: &HashMap<ArgoCdInstance, HashMap<String, AppInfo>>,
+    target_versions: &HashMap<ArgoCdInstance, HashMap<String, String>>,
+    initial_operations: &HashMap<(ArgoCdInstance, String), Option<String>>,
+    spinners: AppSpinners<'a>,
+    failures: &mut Vec<String>,
+) -> AppSpinners<'a> {
+    let mut unsynced_app_spinners = Vec::new();
+
+    for ((instance, name), spinner) in spinners {
+        let env = instance.name();
+        match apps_by_instance[&instance].get(name) {
             Some(app) => {
-                // let target_version = target_versions.get(name).map(|s| s.as_str());
-                let target_version = target_versions.get(name)
-                    .ok_or_else(|| ArcError::UserInputError(format!("Unknown target version for app: {}", name)))?;
-
-                if app.is_version_updated(target_version) && app.is_synced() {
-                    spinner.stop(format!("✅ {}", app.minimal_text(true)));
+                let target_version = &target_versions[&instance][name];
+
+                // A failure only counts once it relates to this deploy, rather than to whatever ran before it
+                let is_new_operation = initial_operations.get(&(instance, name.to_string())) != Some(&app.started_at);
+                let failure = app.failure()
+                    .filter(|_| is_new_operation || app.is_version_updated(target_version));
+
+                if let Some(failure) = failure {
+                    spinner.error(format!("{:<6} {} {}", env, app.minimal_text(app.is_version_updated(target_version)), failure));
+                    failures.push(format!("{} in {} ({})", name, env, failure));
+                } else if app.is_version_updated(target_version) && app.is_synced() {
+                    spinner.stop(format!("✅ {:<6} {}", env, app.minimal_text(true)));
                 } else if app.is_version_updated(target_version) {
-                    spinner.set_message(format!(" {}", app.minimal_text(true)));
-                    unsynced_app_spinners.insert(name, spinner);
+                    spinner.set_message(format!(" {:<6} {}", env, app.minimal_text(true)));
+                    unsynced_app_spinners.push(((instance, name), spinner));
                 } else {
-                    unsynced_app_spinners.insert(name, spinner);
+                    unsynced_app_spinners.push(((instance, name), spinner));
                 }
             },
             None => {
-                spinner.stop(format!("❓ {:<30} {:<23} {:<40}", name, "-", "-"));
+                // The app can't be deployed if ArgoCD doesn't know about it, e.g. due to a typo in the PR
+                spinner.error(format!("{:<6} {:<30} not found in ArgoCD", env, name));
+                failures.push(format!("{} in {} (not found in ArgoCD)", name, env));
             }
         };
     }
 
-    Ok(unsynced_app_spinners)
+    unsynced_app_spinners
 }
 
-async fn parse_github_pr_files(files: &Vec<GithubPrFile>) -> Result<(ArgoCdInstance, HashMap<String, String>), ArcError> {
-    // Grab the first changed file in the PR
-    let first_filename = &files.first()
-        .ok_or_else(|| ArcError::UserInputError("No files found in PR".to_string()))?
-        .filename;
+/// Groups the target version of each app changed by a PR by the ArgoCD instance that deploys it
+fn parse_github_pr_files(
+    files: &[GithubPrFile],
+    projects: &[String],
+) -> Result<HashMap<ArgoCdInstance, HashMap<String, String>>, ArcError> {
+    if files.is_empty() {
+        return Err(ArcError::UserInputError("No files found in PR".to_string()));
+    }
 
-    // Extract environment name from the filename, between the second-to-last and last slashes
-    // e.g., "charts/arc/arc-example/envs/models/us-west-2/stage/version.yaml" -> "stage"
-    let parts: Vec<&str> = first_filename.split('/').collect();
-    let argo_env = if parts.len() >= 2 {
-        parts[parts.len() - 2]
-    } else {
-        return Err(ArcError::UserInputError(format!("Unable to parse environment from filename: {}", first_filename)));
-    };
-
-    // Infer an ArgoCD instance from the environment name
-    let argo_instance = ArgoCdInstance::from(argo_env);
-
-    // Extract list of app names and their target versions from each changed file in the PR
-    let target_versions: HashMap<String, String> = files.iter()
-        .filter_map(|file| {
-            let app_name = extract_arc_app_name(file)?;
-            let target_version = extract_app_target_version(file)?;
-            Some((app_name, target_version))
-        })
-        .collect();
+    let mut target_versions: HashMap<ArgoCdInstance, HashMap<String, String>> = HashMap::new();
+    for file in files {
+        let Some(app_name) = extract_app_name(&file.filename, projects) else {
+            continue;
+        };
+        let Some(argo_instance) = extract_argo_instance(&file.filename) else {
+            continue;
+        };
+        let Some(target_version) = file.added_tag() else {
+            continue;
+        };
+        target_versions.entry(argo_instance).or_default().insert(app_name, target_version);
+    }
 
-    Ok((argo_instance, target_versions))
+    if target_versions.is_empty() {
+        return Err(ArcError::UserInputError("No app version changes found in PR".to_string()));
+    }
+    Ok(target_versions)
 }
 
-fn extract_arc_app_name(pr_file: &GithubPrFile) -> Option<String> {
-    const PREFIX: &str = "charts/arc/";
-
-    if !pr_file.filename.starts_with(PREFIX) {
-        return None;
-    }
+/// Directory of an app's chart in gitops, where each ArgoCD project has its own directory of charts
+pub(crate) fn app_chart_dir(project: &str, app: &str) -> String {
+    format!("charts/{}/{}", project, app)
+}
 
-    // Get the portion after "charts/arc/"
-    let after_prefix = &pr_file.filename[PREFIX.len()..];
+/// Extracts the app whose chart contains a path, if the chart belongs to one of the projects
+/// e.g., "charts/arc/arc-example/envs/models/us-west-2/stage/version.yaml" -> "arc-example"
+pub(crate) fn extract_app_name(path: &str, projects: &[String]) -> Option<String> {
+    // Get the portion after "charts/<project>/"
+    let after_charts = path.strip_prefix("charts/")?;
+    let after_prefix = projects.iter()
+        .find_map(|project| after_charts.strip_prefix(project.as_str())?.strip_prefix('/'))?;
 
     // Find the next "/" and extract the service name
     after_prefix.split('/').next().map(|s| s.to_string())
 }
 
-fn extract_app_target_version(pr_file: &GithubPrFile) -> Option<String> {
-    let patch = pr_file.patch.as_ref()?;
-
-    // Search for lines that start with "+  tag: " in the patch
-    for line in patch.lines() {
-        if line.starts_with("+  tag: ") {
-            // Extract the version after "+  tag: "
-            let version = line["+  tag: ".len()..].trim();
-            // Remove quotes if present
-            let version = version.trim_matches('"').trim_matches('\'');
-            return Some(version.to_string());
-        }
-    }
-
-    None
-}
-
-fn prompt_for_argo_instance() -> Result<ArgoCdInstance, ArcError> {
-    // Get a list of all available ArgoCD instances
-    let available_argo_instances = ArgoCdInstance::all();
-
-    let mut menu = cliclack::select("Select ArgoCD instance");
-    for argo in &available_argo_instances {
-        menu = menu.item(argo.name(), argo.name(), "");
-    }
-
-    let argo_name = menu.interact()?.to_string();
-    Ok(ArgoCdInstance::from(argo_name.as_str()))
+pub(crate) fn extract_argo_instance(path: &str) -> Option<ArgoCdInstance> {
+    // The environment is the directory containing the version file
+    // e.g., "charts/arc/arc-example/envs/models/us-west-2/stage/version.yaml" -> "stage"
+    let dir = path.strip_suffix("/version.yaml")?;
+    let argo_env = dir.rsplit('/').next()?;
+    ArgoCdInstance::all().into_iter().find(|instance| instance.name() == argo_env)
 }
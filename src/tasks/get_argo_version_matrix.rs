@@ -0,0 +1,82 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use async_trait::async_trait;
+use cliclack::{intro, spinner};
+use tokio::task::JoinSet;
+use crate::clients::argo_client::ArgoClient;
+use crate::models::argo::{AppInfo, AppVersions, ArgoCdInstance};
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::goals::{GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+#[derive(Debug)]
+pub struct GetArgoVersionMatrixTask;
+
+#[async_trait]
+impl Task for GetArgoVersionMatrixTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Compare ArgoCD versions")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        _state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::ArgoVersionMatrixKnown { promotable, project } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoVersionMatrixKnown, params));
+        };
+
+        // Logging in may open a browser and listens on a fixed port, so it has to happen one instance at a time
+        let instances = ArgoCdInstance::all();
+        let mut clients = Vec::new();
+        for instance in &instances {
+            let client = Arc::new(ArgoClient::new(*instance, &config.argo)?);
+            client.ensure_logged_in().await?;
+            clients.push((*instance, client));
+        }
+
+        let progress = spinner();
+        progress.start("Fetching applications from all ArgoCD instances...");
+        let projects = config.argo.projects(project.as_deref());
+        let mut requests = JoinSet::new();
+        for (instance, client) in clients {
+            let projects = projects.clone();
+            requests.spawn(async move {
+                let apps = client.fetch_apps(&projects).await?;
+                Ok::<_, ArcError>((instance, apps))
+            });
+        }
+
+        let mut apps_by_instance: HashMap<ArgoCdInstance, HashMap<String, AppInfo>> = HashMap::new();
+        while let Some(result) = requests.join_next().await {
+            let (instance, apps) = result
+                .map_err(|e| ArcError::CommandExecutionError(format!("ArgoCD request failed: {}", e)))??;
+            apps_by_instance.insert(instance, apps);
+        }
+        progress.stop("Fetched applications from all ArgoCD instances");
+
+        let names: BTreeSet<&String> = apps_by_instance.values().flat_map(|apps| apps.keys()).collect();
+        let matrix: Vec<AppVersions> = names.into_iter()
+            .map(|name| AppVersions {
+                name: name.clone(),
+                image_tags: instances.iter()
+                    .map(|instance| apps_by_instance[instance].get(name).map(|app| app.image_tag.clone()))
+                    .collect(),
+            })
+            .filter(|versions| !promotable || versions.differs_from_last())
+            .collect();
+
+        let mut rows = vec![AppVersions::header(), "-".repeat(30 + 31 * instances.len())];
+        rows.extend(matrix.iter().map(|versions| versions.row()));
+
+        let key = if *promotable { "Promotable ArgoCD Applications (differing from prod)" } else { "ArgoCD Application Versions" };
+        let outro_text = OutroText::multi(key.to_string(), rows.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::ArgoVersionMatrix(matrix), outro_text))
+    }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use cliclack::intro;
+use serde_json::Value;
+use crate::clients::vault_client::lookup_token;
+use crate::keyrings::argo_keyring::ArgoKeyring;
+use crate::keyrings::vault_keyring::VaultKeyring;
+use crate::models::argo::ArgoCdInstance;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::goals::GoalParams;
+use crate::models::state::State;
+use crate::models::vault::{VaultInstance, VaultSettings};
+use crate::tasks::perform_sso::{cache_dir, SsoTokenCache};
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+const NOT_LOGGED_IN: &str = "not logged in";
+
+#[derive(Debug)]
+pub struct GetAuthStatusTask;
+
+#[async_trait]
+impl Task for GetAuthStatusTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Auth Status")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        _params: &GoalParams,
+        config: &CliConfig,
+        _state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let mut rows: Vec<(String, String)> = Vec::new();
+
+        for instance in ArgoCdInstance::all() {
+            let status = match ArgoKeyring::new(&instance).get_credentials() {
+                Ok(credentials) => {
+                    let identity = jwt_identity(&credentials.id_token).unwrap_or_else(|| "unknown".to_string());
+                    let refreshable = if credentials.refresh_token.is_some() { ", refreshable" } else { "" };
+                    format!("{}, {}{}", identity, describe_expiry(credentials.expires_at), refreshable)
+                },
+                Err(_) => NOT_LOGGED_IN.to_string(),
+            };
+            rows.push((format!("argo {}", instance.name()), status));
+        }
+
+        for instance in VaultInstance::all() {
+            let status = match VaultKeyring::new(&instance).get_credentials() {
+                Ok(credentials) => {
+                    // Vault tokens are opaque, so ask Vault who the token belongs to
+                    let settings = VaultSettings::new(&instance, config)?;
+                    let identity = match lookup_token(&settings, &credentials.client_token).await {
+                        Ok(token_info) => token_info.display_name,
+                        Err(_) => "invalid or revoked".to_string(),
+                    };
+                    let renewable = if credentials.renewable { ", renewable" } else { "" };
+                    format!("{}, {}{}", identity, describe_expiry(credentials.expires_at), renewable)
+                },
+                Err(_) => NOT_LOGGED_IN.to_string(),
+            };
+            rows.push((format!("vault {}", instance.name()), status));
+        }
+
+        let (sso_sessions, unreadable) = read_sso_sessions()?;
+        if sso_sessions.is_empty() && unreadable.is_empty() {
+            rows.push(("sso".to_string(), NOT_LOGGED_IN.to_string()));
+        }
+        for session in sso_sessions {
+            let refreshable = if session.refresh_token.is_some() { ", refreshable" } else { "" };
+            let expiry = match DateTime::parse_from_rfc3339(&session.expires_at) {
+                Ok(expires_at) => describe_expiry(expires_at.with_timezone(&Utc)),
+                Err(_) => "unknown expiry".to_string(),
+            };
+            rows.push(("sso".to_string(), format!("{}, {}{}", session.start_url, expiry, refreshable)));
+        }
+        for file_name in unreadable {
+            rows.push(("sso".to_string(), format!("unreadable cache file {}", file_name)));
+        }
+
+        let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let summary = rows.iter()
+            .map(|(label, status)| format!("{:<width$}  {}", label, status, width = width))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let outro_text = OutroText::multi("Cached credentials".to_string(), summary);
+        Ok(GoalStatus::Completed(TaskResult::AuthStatus, outro_text))
+    }
+}
+
+/// Extracts a human-readable identity from the (unverified) claims of a JWT
+fn jwt_identity(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+
+    ["email", "name", "sub"].iter()
+        .find_map(|claim| claims[claim].as_str())
+        .map(String::from)
+}
+
+fn describe_expiry(expires_at: DateTime<Utc>) -> String {
+    let remaining = expires_at - Utc::now();
+    let describe = |d: chrono::Duration| match d.num_minutes() {
+        m if m < 60 => format!("{}m", m),
+        m if m < 60 * 24 => format!("{}h {}m", m / 60, m % 60),
+        m => format!("{}d", m / (60 * 24)),
+    };
+
+    if remaining > chrono::Duration::zero() {
+        format!("expires in {}", describe(remaining))
+    } else {
+        format!("expired {} ago", describe(-remaining))
+    }
+}
+
+/// Reads all cached AWS SSO access tokens, along with the names of cache files that couldn't be read.
+/// Client registrations share the directory but are skipped, since they hold no access token.
+fn read_sso_sessions() -> Result<(Vec<SsoTokenCache>, Vec<String>), ArcError> {
+    let dir = cache_dir()?;
+    if !dir.exists() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let mut sessions = Vec::new();
+    let mut unreadable = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let cache = std::fs::read_to_string(&path).ok()
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok());
+        let session = match cache {
+            Some(cache) if cache.get("accessToken").is_none() => continue,
+            Some(cache) => serde_json::from_value::<SsoTokenCache>(cache).ok(),
+            None => None,
+        };
+        match session {
+            Some(session) => sessions.push(session),
+            None => unreadable.push(path.file_name().unwrap_or_default().to_string_lossy().to_string()),
+        }
+    }
+    Ok((sessions, unreadable))
+}
@@ -71,7 +71,7 @@ impl Task for GetAwsSecretTask {
             .ok_or_else(|| ArcError::UnparseableSecret(secret_name))?;
 
         let key = "Secret Value".to_string();
-        let outro_text = OutroText::single(key, secret_value.clone());
+        let outro_text = OutroText::secret(key, secret_value.clone());
         Ok(GoalStatus::Completed(TaskResult::AwsSecret(secret_value), outro_text))
     }
 }
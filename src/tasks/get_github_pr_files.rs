@@ -1,21 +1,15 @@
-use cliclack::{intro, select};
+use cliclack::{intro, log, select};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use tokio::process::Command;
+use chrono::Utc;
 use crate::{GoalStatus, OutroText};
-use crate::models::github::{get_github_app_token, get_installation_id};
-use crate::models::github::GithubPr;
-use crate::models::github::GithubPrFile;
+use crate::clients::github_client::GithubClient;
+use crate::models::github::{GithubPr, PrLocator};
 use crate::models::config::CliConfig;
 use crate::models::errors::ArcError;
 use crate::models::goals::{Goal, GoalParams, GoalType};
 use crate::models::state::State;
 use crate::tasks::{Task, TaskResult};
-use reqwest::header::{AUTHORIZATION, USER_AGENT};
-use crate::models::aws_profile::AwsAccount;
-
-pub const SECRET_PATH: &str = "mp/arcli-backend-services-gitops";
-pub const APP_ID_FIELD: &str = "APP_ID";
-pub const PRIVATE_KEY_FIELD: &str = "PRIVATE_KEY";
 
 #[derive(Debug)]
 pub struct GetGithubPrFilesTask;
@@ -33,70 +27,53 @@ impl Task for GetGithubPrFilesTask {
         _config: &CliConfig,
         state: &State
     ) -> Result<GoalStatus, ArcError> {
-        // Fetch GitHub AppID from dev namespace of NonProd Vault (any vault instance would do)
-        let aws_account = Some(AwsAccount::Dev);
-        let app_id_goal = Goal::vault_secret_known(
-            SECRET_PATH.to_string(), Some(APP_ID_FIELD.to_string()), aws_account, None
-        );
-        if !state.contains(&app_id_goal) {
-            return Ok(GoalStatus::Needs(app_id_goal));
-        }
-
-        // Fetch GitHub AppID from dev namespace of NonProd Vault (any vault instance would do)
-        let private_key_goal = Goal::vault_secret_known(
-            SECRET_PATH.to_string(), Some(PRIVATE_KEY_FIELD.to_string()), aws_account, None
-        );
-        if !state.contains(&private_key_goal) {
-            return Ok(GoalStatus::Needs(private_key_goal));
+        let token_goal = Goal::github_token_known();
+        if !state.contains(&token_goal) {
+            return Ok(GoalStatus::Needs(token_goal));
         }
-
-        // Retrieve GitHub AppID and private key from state
-        let app_id = state.get_vault_secret(&app_id_goal)?;
-        let private_key = state.get_vault_secret(&private_key_goal)?;
-
-        let client = reqwest::Client::new();
-
-        // Fetch installation ID for the GitHub App (assuming single installation for org app)
-        let installation_id = get_installation_id(&client, &app_id, &private_key).await?;
-
-        // Fetch GitHub API access token for the app installation
-        let token = get_github_app_token(&client, &app_id, &private_key, &installation_id).await?;
+        let client = GithubClient::new(state.get_github_token(&token_goal)?);
 
         // Extract goal parameters
-        let (repo, pr) = match params {
-            GoalParams::GithubPrFilesKnown { repo, pull_request: Some(pr), .. } => (repo, *pr),
-            GoalParams::GithubPrFilesKnown { repo, pull_request: _, lookback_duration: Some(duration), .. } => {
+        let GoalParams::GithubPrFilesKnown { repo, pr_locator } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::GithubPrFilesKnown, params));
+        };
+
+        let pr = match pr_locator {
+            PrLocator::Number(pr) => *pr,
+            PrLocator::RecentlyOpened(duration) => {
                 // Prompt user to select a PR that was opened within the specified window duration
-                let selected_pr = prompt_to_select_recently_opened_pr(&client, repo, &token, duration).await?;
-                (repo, selected_pr)
+                prompt_to_select_recently_opened_pr(&client, repo, duration).await?
+            },
+            PrLocator::Branch(branch) => {
+                let prs = client.fetch_branch_prs(repo, branch).await?;
+                select_located_pr(prs, &format!("branch '{}'", branch))?
+            },
+            PrLocator::Commit(sha) => {
+                let prs = client.fetch_commit_prs(repo, sha).await?;
+                select_located_pr(prs, &format!("commit {}", sha))?
+            },
+            PrLocator::CurrentBranch => {
+                let branch = current_branch(repo).await?;
+                let prs = client.fetch_branch_prs(repo, &branch).await?;
+                select_located_pr(prs, &format!("branch '{}'", branch))?
             },
-            _ => return Err(ArcError::invalid_goal_params(GoalType::GithubPrFilesKnown, params)),
         };
 
-        // Construct the GitHub API URL for listing files changed in the PR
-        let api_url = format!("https://api.github.com/repos/agilityrobotics/{repo}/pulls/{pr}/files");
-
         // Query GitHub API for list of files changed in the PR
-        let req = client.get(api_url)
-            .header(USER_AGENT, "rust-github-pr-list")
-            .header("Accept", "application/vnd.github.v3+json")
-            .header(AUTHORIZATION, format!("Bearer {}", token));
-
-        let files: Vec<GithubPrFile> = req.send().await?.json().await?;
+        let files = client.fetch_pr_files(repo, pr).await?;
         Ok(GoalStatus::Completed(TaskResult::GithubPrFiles(files), OutroText::None))
     }
 }
 
 async fn prompt_to_select_recently_opened_pr(
-    client: &reqwest::Client,
+    client: &GithubClient,
     repo: &str,
-    token: &str,
     duration: &std::time::Duration
 ) -> Result<u32, ArcError> {
     // Query GitHub API for all open PRs created within the past window_duration
     let minutes = duration.as_secs() / 60;
     let cutoff_time = Utc::now() - chrono::Duration::minutes(minutes as i64);
-    let prs = query_recent_prs(client, repo, token, cutoff_time).await?;
+    let prs = client.fetch_open_prs(repo, cutoff_time).await?;
 
     if prs.is_empty() {
         return Err(ArcError::UserInputError(format!("No open PRs found in the last {} minutes", minutes)));
@@ -113,27 +90,43 @@ async fn prompt_to_select_recently_opened_pr(
     Ok(selected as u32)
 }
 
-async fn query_recent_prs(
-    client: &reqwest::Client,
-    repo: &str,
-    token: &str,
-    cutoff_time: DateTime<Utc>,
-) -> Result<Vec<GithubPr>, ArcError> {
-    let api_url = format!("https://api.github.com/repos/agilityrobotics/{repo}/pulls?state=open&sort=created&direction=desc&per_page=100");
-
-    let response = client.get(&api_url)
-        .header(USER_AGENT, "rust-github-pr-list")
-        .header("Accept", "application/vnd.github.v3+json")
-        .header(AUTHORIZATION, format!("Bearer {}", token))
-        .send()
-        .await?;
-
-    let all_prs: Vec<GithubPr> = response.json().await?;
-
-    // Filter PRs created after cutoff time
-    let recent_prs: Vec<GithubPr> = all_prs.into_iter()
-        .filter(|pr| pr.created_at > cutoff_time)
-        .collect();
-
-    Ok(recent_prs)
-}
\ No newline at end of file
+/// Picks the PR that a branch or commit belongs to, preferring open PRs over ones that were already
+/// closed or merged, and otherwise the most recently created one
+fn select_located_pr(prs: Vec<GithubPr>, location: &str) -> Result<u32, ArcError> {
+    let pr = prs.iter()
+        .find(|pr| pr.state == "open")
+        .or_else(|| prs.first())
+        .ok_or_else(|| ArcError::UserInputError(format!("No PR found for {}", location)))?;
+
+    log::info(format!("Found PR #{} - {} (by @{}) for {}", pr.number, pr.title, pr.user.login, location))?;
+    Ok(pr.number as u32)
+}
+
+/// Reads the current branch of the git checkout in the working directory, which must be a clone of the repo
+async fn current_branch(repo: &str) -> Result<String, ArcError> {
+    let remote_url = git_output(&["remote", "get-url", "origin"]).await?;
+    let remote_repo = remote_url.trim_end_matches('/').trim_end_matches(".git").rsplit(['/', ':']).next();
+    if remote_repo != Some(repo) {
+        return Err(ArcError::UserInputError(format!("The current directory isn't a checkout of {} (origin is {})", repo, remote_url)));
+    }
+
+    let branch = git_output(&["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    if branch == "HEAD" {
+        return Err(ArcError::UserInputError("The checkout has no current branch (detached HEAD), use --commit instead".to_string()));
+    }
+    Ok(branch)
+}
+
+async fn git_output(args: &[&str]) -> Result<String, ArcError> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| ArcError::CommandExecutionError(format!("Unable to run git: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ArcError::CommandExecutionError(format!("git {} failed: {}", args.join(" "), stderr.trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use cliclack::{intro, log, spinner};
+use crate::clients::github_client::GithubClient;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::github::GithubWorkflowRun;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+// Number of runs shown, most recent first
+const RUN_LIMIT: usize = 10;
+
+// Number of recent runs of every workflow searched for those of the build workflow
+const RUN_LOOKBACK: usize = 100;
+const POLL_INTERVAL_SECS: u64 = 10;
+
+#[derive(Debug)]
+pub struct GetGithubRunsTask;
+
+#[async_trait]
+impl Task for GetGithubRunsTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Get GitHub Actions runs")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::GithubRunsKnown { repo, branch, workflow, wait } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::GithubRunsKnown, params));
+        };
+
+        let token_goal = Goal::github_token_known();
+        if !state.contains(&token_goal) {
+            return Ok(GoalStatus::Needs(token_goal));
+        }
+        let client = GithubClient::new(state.get_github_token(&token_goal)?);
+
+        // Only the build workflow publishes an image, so without one the runs are shown without tags
+        let build_workflow = workflow.as_deref().or(config.github.build_workflow(repo));
+        let image_tag_template = build_workflow.map(|_| config.github.image_tag_template(repo));
+
+        let runs = match build_workflow {
+            Some(build_workflow) => client.fetch_workflow_runs(repo, branch, RUN_LOOKBACK).await?
+                .into_iter()
+                .filter(|run| run.is_workflow(build_workflow))
+                .take(RUN_LIMIT)
+                .collect(),
+            None => client.fetch_workflow_runs(repo, branch, RUN_LIMIT).await?,
+        };
+
+        if *wait {
+            let Some(build_workflow) = build_workflow else {
+                return Err(ArcError::UserInputError(format!(
+                    "No build workflow is configured for {}, so pass --workflow or set github.repos.{}.build_workflow",
+                    repo, repo
+                )));
+            };
+            let latest = runs.into_iter().next()
+                .ok_or_else(|| ArcError::UserInputError(format!("No runs of {} found for {} on {}", build_workflow, repo, branch)))?;
+            let run = wait_for_run(&client, repo, latest).await?;
+
+            // Fail the command unless the run succeeded, so that scripts can gate on the image being published
+            if !run.is_successful() {
+                return Err(ArcError::CommandExecutionError(format!(
+                    "Run #{} of {} finished with conclusion '{}': {}",
+                    run.run_number,
+                    run.name.as_deref().unwrap_or(repo),
+                    run.outcome(),
+                    run.html_url
+                )));
+            }
+
+            let outro_text = OutroText::single("Expected Image Tag".to_string(), run.image_tag(config.github.image_tag_template(repo)));
+            return Ok(GoalStatus::Completed(TaskResult::GithubRuns(vec![run]), outro_text));
+        }
+
+        if build_workflow.is_none() {
+            log::warning(format!(
+                "No build workflow is configured for {}, so expected image tags aren't shown. Pass --workflow or set github.repos.{}.build_workflow",
+                repo, repo
+            ))?;
+        }
+
+        let mut rows = vec![GithubWorkflowRun::header(), "-".repeat(115)];
+        rows.extend(runs.iter().map(|run| run.row(image_tag_template)));
+
+        let outro_text = OutroText::multi(format!("GitHub Actions Runs ({}, {})", repo, branch), rows.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::GithubRuns(runs), outro_text))
+    }
+}
+
+/// Polls a run until it completes, showing its latest status alongside a spinner
+async fn wait_for_run(client: &GithubClient, repo: &str, mut run: GithubWorkflowRun) -> Result<GithubWorkflowRun, ArcError> {
+    let progress = spinner();
+    let describe = |run: &GithubWorkflowRun| format!(
+        "Run #{} of {} ({}) is {}",
+        run.run_number,
+        run.name.as_deref().unwrap_or(repo),
+        run.short_sha(),
+        run.outcome()
+    );
+    progress.start(describe(&run));
+
+    while !run.is_completed() {
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        run = client.fetch_workflow_run(repo, run.id).await?;
+        progress.set_message(describe(&run));
+    }
+
+    if run.is_successful() {
+        progress.stop(describe(&run));
+    } else {
+        progress.error(describe(&run));
+    }
+    Ok(run)
+}
@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use cliclack::intro;
+use tokio::process::Command;
+use crate::clients::github_client::{GithubClient, GITHUB_ORG};
+use crate::keyrings::github_keyring::GithubKeyring;
+use crate::{GoalStatus, OutroText};
+use crate::models::aws_profile::AwsAccount;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::github::github_app_jwt;
+use crate::models::goals::{Goal, GoalParams};
+use crate::models::state::State;
+use crate::tasks::{Task, TaskResult};
+
+pub const SECRET_PATH: &str = "mp/arcli-backend-services-gitops";
+pub const APP_ID_FIELD: &str = "APP_ID";
+pub const PRIVATE_KEY_FIELD: &str = "PRIVATE_KEY";
+
+// Mint a new installation token slightly before the cached one expires, so it doesn't expire mid-command
+const REFRESH_THRESHOLD_SECS: i64 = 300;
+
+#[derive(Debug)]
+pub struct GetGithubTokenTask;
+
+#[async_trait]
+impl Task for GetGithubTokenTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Get GitHub Token")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        _params: &GoalParams,
+        _config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        // Prefer a token that the user already has, since the GitHub App's credentials require a Vault login
+        if let Some(token) = std::env::var("GITHUB_TOKEN").ok().filter(|token| !token.trim().is_empty()) {
+            return Ok(GoalStatus::Completed(TaskResult::GithubToken(token), OutroText::None));
+        }
+        if let Some(token) = gh_auth_token().await {
+            return Ok(GoalStatus::Completed(TaskResult::GithubToken(token), OutroText::None));
+        }
+
+        // Installation tokens are valid for an hour, so reuse a cached one until it's about to expire
+        let keyring = GithubKeyring::new(GITHUB_ORG);
+        let cached_token = keyring.get_token().ok()
+            .filter(|cached| cached.expires_at > Utc::now() + chrono::Duration::seconds(REFRESH_THRESHOLD_SECS));
+        if let Some(cached_token) = cached_token {
+            return Ok(GoalStatus::Completed(TaskResult::GithubToken(cached_token.token), OutroText::None));
+        }
+
+        // Fetch GitHub AppID from dev namespace of NonProd Vault (any vault instance would do)
+        let aws_account = Some(AwsAccount::Dev);
+        let app_id_goal = Goal::vault_secret_known(
+            SECRET_PATH.to_string(), Some(APP_ID_FIELD.to_string()), aws_account, None
+        );
+        if !state.contains(&app_id_goal) {
+            return Ok(GoalStatus::Needs(app_id_goal));
+        }
+
+        // Fetch GitHub App private key from dev namespace of NonProd Vault (any vault instance would do)
+        let private_key_goal = Goal::vault_secret_known(
+            SECRET_PATH.to_string(), Some(PRIVATE_KEY_FIELD.to_string()), aws_account, None
+        );
+        if !state.contains(&private_key_goal) {
+            return Ok(GoalStatus::Needs(private_key_goal));
+        }
+
+        // Retrieve GitHub AppID and private key from state
+        let app_id = state.get_vault_secret(&app_id_goal)?;
+        let private_key = state.get_vault_secret(&private_key_goal)?;
+
+        let app_client = GithubClient::new(&github_app_jwt(&app_id, &private_key)?);
+
+        // Fetch installation ID for the GitHub App's installation in our org
+        let installation_id = app_client.fetch_installation_id(GITHUB_ORG).await?;
+
+        // Fetch GitHub API access token for the app installation
+        let installation_token = app_client.create_installation_token(installation_id).await?;
+        keyring.save_token(&installation_token.token, installation_token.expires_at)?;
+
+        Ok(GoalStatus::Completed(TaskResult::GithubToken(installation_token.token), OutroText::None))
+    }
+}
+
+/// Returns the token that the GitHub CLI is logged in with, if it's installed and logged in
+async fn gh_auth_token() -> Option<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token"])
+        .output()
+        .await
+        .ok()?;
+
+    let token = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use cliclack::{intro, select};
+use cliclack::{intro, select, spinner};
 use crate::tasks::{Task, TaskResult};
 use crate::clients::vault_client::VaultClient;
 use crate::models::errors::ArcError;
@@ -21,12 +21,12 @@ impl Task for GetVaultSecretTask {
     async fn execute(
         &self,
         params: &GoalParams,
-        _config: &CliConfig,
+        config: &CliConfig,
         state: &State
     ) -> Result<GoalStatus, ArcError> {
         let client = match params {
             GoalParams::VaultSecretKnown{ aws_account: Some(account), .. } => {
-                VaultClient::new(account)
+                VaultClient::new(account, config)?
             },
             GoalParams::VaultSecretKnown{ aws_profile, .. } => {
                 // If AWS account wasn't provided, we'll infer it from an AWS profile
@@ -40,7 +40,7 @@ impl Task for GetVaultSecretTask {
                 let profile_info = state.get_aws_profile_info(&profile_goal)?;
 
                 // Create client for interacting with Vault
-                VaultClient::new(&profile_info.account)
+                VaultClient::new(&profile_info.account, config)?
             },
             _ => return Err(ArcError::invalid_goal_params(GoalType::VaultSecretKnown, params)),
         };
@@ -57,14 +57,14 @@ impl Task for GetVaultSecretTask {
             GoalParams::VaultSecretKnown{ field: Some(f), .. } => {
                 // Extract a specific secret field
                 let secret_field = client.guarded_read_secret_field(&secret_path, f).await?;
-                let outro_msg = OutroText::multi(f.clone(), secret_field.clone());
+                let outro_msg = OutroText::secret(f.clone(), secret_field.clone());
                 (secret_field, outro_msg)
             },
             GoalParams::VaultSecretKnown{ field: None, .. } => {
                 // Concatenate k: v pairs into a single, newline-delimited string
                 let all_fields = client.guarded_read_secret(&secret_path).await?;
                 let prompt = "Secret Value".to_string();
-                let outro_msg = OutroText::multi(prompt, all_fields.clone());
+                let outro_msg = OutroText::secret_fields(prompt, all_fields.clone());
                 (all_fields, outro_msg)
             },
             _ => return Err(ArcError::invalid_goal_params(GoalType::VaultSecretKnown, params)),
@@ -75,20 +75,22 @@ impl Task for GetVaultSecretTask {
     }
 }
 
+/// Prompts for a secret from a flat list of every secret's full path, so that typing any fragment
+/// of a path, however deeply nested, filters the list down to it
 async fn prompt_for_secret_path(client: &VaultClient) -> Result<String, ArcError> {
-    let mut current_path = String::new();
+    // Listings are cached, so only the first prompt in a while has to walk the whole tree
+    let progress = spinner();
+    progress.start("Listing secrets...");
+    let paths = client.guarded_list_tree("").await?;
+    progress.stop(format!("Found {} secrets", paths.len()));
 
-    while current_path.is_empty() || current_path.ends_with('/') {
-        // Collect all available sub-paths
-        let available_paths = client.guarded_list_paths(&current_path).await?;
-
-        // Prompt user to select a path
-        let mut menu = select("Select a secret path");
-        for path in &available_paths {
-            menu = menu.item(path, path, "");
-        }
-        current_path = menu.interact()?.to_string();
+    if paths.is_empty() {
+        return Err(ArcError::UserInputError("No secrets found that you're allowed to list".to_string()));
     }
 
-    Ok(current_path.to_string())
-}
\ No newline at end of file
+    let mut menu = select("Select a secret (type to filter)").filter_mode();
+    for path in &paths {
+        menu = menu.item(path.clone(), path.clone(), "");
+    }
+    Ok(menu.interact()?)
+}
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use cliclack::intro;
+use crate::tasks::{Task, TaskResult};
+use crate::clients::vault_client::VaultClient;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::vault::VaultSecretVersionInfo;
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+
+#[derive(Debug)]
+pub struct GetVaultSecretHistoryTask;
+
+#[async_trait]
+impl Task for GetVaultSecretHistoryTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Get Vault Secret History")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::VaultSecretHistoryKnown { path, aws_profile } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::VaultSecretHistoryKnown, params));
+        };
+
+        // Infer the AWS account, and therefore the Vault instance, from an AWS profile
+        let profile_goal = Goal::aws_profile_selected(aws_profile.clone());
+        if !state.contains(&profile_goal) {
+            return Ok(GoalStatus::Needs(profile_goal));
+        }
+        let profile_info = state.get_aws_profile_info(&profile_goal)?;
+        let client = VaultClient::new(&profile_info.account, config)?;
+
+        let versions = client.guarded_read_secret_history(path).await?
+            .ok_or_else(|| ArcError::UserInputError(format!("Vault secret '{}' does not exist", path)))?;
+
+        let outro_text = OutroText::multi(path.clone(), format_history(&versions));
+        Ok(GoalStatus::Completed(TaskResult::VaultSecretHistory(versions), outro_text))
+    }
+}
+
+fn format_history(versions: &[VaultSecretVersionInfo]) -> String {
+    let time_format = "%Y-%m-%d %H:%M:%S";
+
+    versions.iter()
+        .map(|v| {
+            let created = v.created_time.with_timezone(&chrono::Local).format(time_format);
+            let status = if v.destroyed {
+                "destroyed".to_string()
+            } else if let Some(deleted) = v.deletion_time {
+                format!("deleted {}", deleted.with_timezone(&chrono::Local).format(time_format))
+            } else {
+                String::new()
+            };
+            format!("v{:<4} created {}  {}", v.version, created, status).trim_end().to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -62,7 +62,7 @@ impl Task for LaunchInfluxTask {
             .as_str()
             .ok_or_else(|| ArcError::invalid_secret("password"))?;
 
-        let outro_text = OutroText::multi(
+        let outro_text = OutroText::secret_fields(
             "Influx Credentials".to_string(),
             format!("username: {}\npassword: {}", username, password),
         );
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use cliclack::{intro, spinner};
+use crate::tasks::{Task, TaskResult};
+use crate::clients::vault_client::VaultClient;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+
+#[derive(Debug)]
+pub struct ListVaultSecretsTask;
+
+#[async_trait]
+impl Task for ListVaultSecretsTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("List Vault Secrets")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::VaultSecretPathsKnown { prefix, recursive, aws_profile } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::VaultSecretPathsKnown, params));
+        };
+
+        // Infer the AWS account, and therefore the Vault instance, from an AWS profile
+        let profile_goal = Goal::aws_profile_selected(aws_profile.clone());
+        if !state.contains(&profile_goal) {
+            return Ok(GoalStatus::Needs(profile_goal));
+        }
+        let profile_info = state.get_aws_profile_info(&profile_goal)?;
+        let client = VaultClient::new(&profile_info.account, config)?;
+
+        let prefix = normalize_prefix(prefix.as_deref());
+        let (paths, listing) = if *recursive {
+            let progress = spinner();
+            progress.start("Listing secrets...");
+            let paths = client.guarded_list_tree(&prefix).await?;
+            progress.stop(format!("Found {} secrets", paths.len()));
+
+            let listing = render_tree(&prefix, &paths);
+            (paths, listing)
+        } else {
+            let paths = client.guarded_list_paths(&prefix).await?;
+            let listing = paths.iter()
+                .map(|p| p.strip_prefix(&prefix).unwrap_or(p))
+                .collect::<Vec<&str>>()
+                .join("\n");
+            (paths, listing)
+        };
+
+        let key = if prefix.is_empty() { "/".to_string() } else { prefix };
+        let outro_text = OutroText::multi(key, listing);
+        Ok(GoalStatus::Completed(TaskResult::VaultSecretPaths(paths), outro_text))
+    }
+}
+
+/// Vault only lists paths beneath a "directory", so ensure that a non-empty prefix ends with a slash
+pub(crate) fn normalize_prefix(prefix: Option<&str>) -> String {
+    match prefix.map(|p| p.trim_start_matches('/')) {
+        None | Some("") => String::new(),
+        Some(p) if p.ends_with('/') => p.to_string(),
+        Some(p) => format!("{}/", p),
+    }
+}
+
+/// Renders a sorted list of secret paths as an indented tree, relative to the prefix
+fn render_tree(prefix: &str, paths: &[String]) -> String {
+    let mut lines = Vec::new();
+    let mut previous: Vec<&str> = Vec::new();
+
+    for path in paths {
+        let components: Vec<&str> = path.strip_prefix(prefix).unwrap_or(path).split('/').collect();
+        let (secret, parents) = components.split_last().expect("split always yields at least one component");
+
+        // Only print the parent directories that differ from those of the previous path
+        let shared = parents.iter()
+            .zip(previous.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, parent) in parents.iter().enumerate().skip(shared) {
+            lines.push(format!("{}{}/", "  ".repeat(depth), parent));
+        }
+        lines.push(format!("{}{}", "  ".repeat(parents.len()), secret));
+
+        previous = parents.to_vec();
+    }
+
+    lines.join("\n")
+}
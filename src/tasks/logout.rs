@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use aws_sdk_sso as sso;
+use cliclack::{intro, log};
+use crate::clients::vault_cache::delete_cache_files;
+use crate::clients::vault_client::revoke_token;
+use crate::keyrings::argo_keyring::ArgoKeyring;
+use crate::keyrings::vault_keyring::VaultKeyring;
+use crate::models::argo::ArgoCdInstance;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::goals::{GoalParams, GoalType};
+use crate::models::state::State;
+use crate::models::vault::{VaultInstance, VaultSettings};
+use crate::tasks::perform_sso::{cache_dir, SsoTokenCache};
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+#[derive(Debug)]
+pub struct LogoutTask;
+
+#[async_trait]
+impl Task for LogoutTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Logout")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        _state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::CredentialsCleared { argo, vault, sso, all } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::CredentialsCleared, params));
+        };
+
+        let argo_instances = match (all, argo) {
+            (true, _) => ArgoCdInstance::all(),
+            (false, Some(name)) => vec![ArgoCdInstance::from(name.as_str())],
+            (false, None) => Vec::new(),
+        };
+        let vault_instances = match (all, vault) {
+            (true, _) => VaultInstance::all(),
+            (false, Some(name)) => vec![VaultInstance::from(name.as_str())],
+            (false, None) => Vec::new(),
+        };
+
+        let mut summary = Vec::new();
+
+        // ArgoCD has no endpoint for revoking tokens, so they can only be forgotten
+        for instance in argo_instances {
+            let status = match ArgoKeyring::new(&instance).delete_credentials()? {
+                true => "logged out",
+                false => "not logged in",
+            };
+            summary.push(format!("argo {}: {}", instance.name(), status));
+        }
+
+        for instance in vault_instances {
+            let keyring = VaultKeyring::new(&instance);
+            if let Ok(credentials) = keyring.get_credentials() {
+                // Revoke the token so that it can't be used even if a copy of it exists elsewhere
+                let settings = VaultSettings::new(&instance, config)?;
+                if let Err(e) = revoke_token(&settings, &credentials.client_token).await {
+                    log::warning(format!("Unable to revoke Vault {} token: {}", instance.name(), e))?;
+                }
+            }
+
+            let status = match keyring.delete_credentials()? {
+                true => "logged out",
+                false => "not logged in",
+            };
+            delete_cache_files(&instance)?;
+            summary.push(format!("vault {}: {}", instance.name(), status));
+        }
+
+        if *sso || *all {
+            let deleted = delete_sso_cache().await?;
+            summary.push(format!("sso: deleted {} cached token(s)", deleted));
+        }
+
+        let outro_text = OutroText::multi("Logged out".to_string(), summary.join("\n"));
+        Ok(GoalStatus::Completed(TaskResult::CredentialsCleared, outro_text))
+    }
+}
+
+/// Signs out of each cached AWS SSO session and then deletes all cached tokens and client
+/// registrations, the same as `aws sso logout`
+async fn delete_sso_cache() -> Result<usize, ArcError> {
+    let dir = cache_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut deleted = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            // Client registrations share the directory but hold no session to sign out of
+            let session = std::fs::read_to_string(&path).ok()
+                .and_then(|data| serde_json::from_str::<SsoTokenCache>(&data).ok());
+            if let Some(session) = session {
+                // Revoke the session so that its token and the role credentials issued with it can't be used
+                if let Err(e) = sso_logout(&session).await {
+                    log::warning(format!("Unable to sign out of SSO session {}: {}", session.start_url, e))?;
+                }
+            }
+
+            std::fs::remove_file(path)?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+async fn sso_logout(session: &SsoTokenCache) -> Result<(), String> {
+    // Create AWS config with no credentials, since the access token is all that the call needs
+    let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(aws_config::Region::new(session.region.clone()))
+        .no_credentials()
+        .load()
+        .await;
+
+    sso::Client::new(&aws_config)
+        .logout()
+        .access_token(&session.access_token)
+        .send()
+        .await
+        .map_err(|e| sso::error::DisplayErrorContext(e).to_string())?;
+    Ok(())
+}
@@ -6,12 +6,11 @@ use sha1::{Sha1, Digest};
 use aws_sdk_ssooidc as ssooidc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tiny_http::{Server, Response};
 use openidconnect::{CsrfToken, PkceCodeChallenge};
-use url::Url;
 use crate::{GoalStatus, OutroText};
 use crate::models::get_env_configs;
 use crate::models::config::CliConfig;
+use crate::clients::oidc_loopback::LoopbackServer;
 use crate::models::errors::ArcError;
 use crate::models::goals::GoalParams;
 use crate::models::state::State;
@@ -62,16 +61,9 @@ impl Task for PerformSsoTask {
                     .ok_or_else(|| ArcError::AwsSsoError("sso_start_url not found in sso_session".to_string()))?
                     .to_string();
 
-                // Start local HTTP server to receive OAuth callback
-                let redirect_host = "127.0.0.1:0";
-                let http_server = Server::http(redirect_host)
-                    .map_err(|e| ArcError::AwsSsoError(format!("Failed to start HTTP server: {}", e)))?;
-
-                let port = match http_server.server_addr() {
-                    tiny_http::ListenAddr::IP(socket_addr) => socket_addr.port(),
-                    _ => return Err(ArcError::AwsSsoError("Unexpected server address type".to_string())),
-                };
-                let redirect_uri = format!("http://127.0.0.1:{}/oauth/callback", port);
+                // Start local HTTP server to receive OAuth callback, on any available port
+                let loopback = LoopbackServer::bind("127.0.0.1", 0, "/oauth/callback", "AWS SSO")?;
+                let redirect_uri = loopback.redirect_uri().to_string();
 
                 // Build client name following AWS CLI pattern
                 let client_name = format!("botocore-client-{}", sso_session_name);
@@ -151,13 +143,8 @@ impl Task for PerformSsoTask {
                     eprintln!("Please manually execute 'models sso login'");
                 }
 
-                // Wait for OAuth callback with authorization code
-                let (auth_code, returned_state) = wait_for_oauth_callback(http_server)?;
-
-                // Verify state parameter
-                if &returned_state != csrf_token.secret() {
-                    return Err(ArcError::AwsSsoError("State parameter mismatch".to_string()));
-                }
+                // Wait for OAuth callback with authorization code, which must carry the same state
+                let callback = loopback.wait_for_callback(csrf_token.secret())?;
 
                 // Exchange authorization code for tokens using AWS SDK
                 // CreateToken is also an unauthenticated endpoint
@@ -168,15 +155,19 @@ impl Task for PerformSsoTask {
                     .await;
                 let ssooidc_client = ssooidc::Client::new(&aws_config);
 
-                let sso_cache = exchange_code_for_token(
+                let sso_cache = match exchange_code_for_token(
                     &ssooidc_client,
                     &registration,
-                    &auth_code,
+                    &callback.code,
                     pkce_verifier.secret(),
                     &redirect_uri,
                     &sso_start_url,
                     &sso_region,
-                ).await?;
+                ).await {
+                    Ok(sso_cache) => sso_cache,
+                    Err(e) => return Err(callback.respond_error(e)),
+                };
+                callback.respond_success()?;
 
                 // Cache token using session name hash (AWS CLI Python behavior)
                 save_token(&sso_cache, &sso_token_path).await?;
@@ -215,7 +206,7 @@ fn get_hashed_cache_path(hash_item: &str) -> Result<PathBuf, ArcError> {
     Ok(path)
 }
 
-fn cache_dir() -> Result<PathBuf, ArcError> {
+pub(crate) fn cache_dir() -> Result<PathBuf, ArcError> {
     let mut cache_path = home::home_dir().ok_or_else(|| ArcError::HomeDirError)?;
     cache_path.push(".aws");
     cache_path.push("sso");
@@ -325,14 +316,6 @@ async fn try_refresh_token(
     Ok(true)
 }
 
-//TODO move this into a shared utils module
-fn extract_query_param(url: &Url, key: &str) -> Result<String, ArcError> {
-    url.query_pairs()
-        .find(|(k, _)| k == key)
-        .map(|(_, value)| value.into_owned())
-        .ok_or_else(|| ArcError::UrlQueryParamError(url.clone(), key.to_string()))
-}
-
 fn build_aws_sso_authorization_url(
     sso_region: &str,
     client_id: &str,
@@ -359,53 +342,6 @@ fn build_aws_sso_authorization_url(
     Ok(auth_url)
 }
 
-fn wait_for_oauth_callback(server: Server) -> Result<(String, String), ArcError> {
-    const TIMEOUT_SECS: u64 = 600; // 10 minutes
-    let start = std::time::Instant::now();
-
-    for request in server.incoming_requests() {
-        if start.elapsed().as_secs() > TIMEOUT_SECS {
-            return Err(ArcError::AwsSsoError("Timeout waiting for OAuth callback".to_string()));
-        }
-
-        let request_url = Url::parse(&format!("http://localhost{}", request.url()))?;
-
-        // Check for errors first
-        if let Ok(error) = extract_query_param(&request_url, "error") {
-            // Send error response to browser
-            let html = format!(r#"
-                <!DOCTYPE html>
-                <html>
-                <head><title>AWS SSO Login</title></head>
-                <body>
-                    <h1>Authorization Failed</h1>
-                    <p>OAuth error: {}</p>
-                    <p>You may close this window and return to the CLI.</p>
-                </body>
-                </html>
-            "#, error);
-
-            let response = Response::from_string(html)
-                .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap());
-            let _ = request.respond(response);
-
-            return Err(ArcError::AwsSsoError(format!("OAuth error: {}", error)));
-        }
-
-        // Extract authorization code and state
-        let code = extract_query_param(&request_url, "code")?;
-        let state = extract_query_param(&request_url, "state")?;
-
-        // Send success response to browser
-        let response = crate::clients::auth_success_response("SSO")?;
-        let _ = request.respond(response);
-
-        return Ok((code, state));
-    }
-
-    Err(ArcError::AwsSsoError("Server closed without receiving auth code".to_string()))
-}
-
 async fn exchange_code_for_token(
     client: &ssooidc::Client,
     registration: &ClientRegistrationCache,
@@ -497,14 +433,14 @@ async fn save_token(token_cache: &SsoTokenCache, path: &PathBuf) -> Result<(), A
 }
 
 #[derive(Serialize, Deserialize)]
-struct SsoTokenCache {
+pub(crate) struct SsoTokenCache {
     #[serde(rename = "startUrl")]
-    start_url: String,
-    region: String,
+    pub(crate) start_url: String,
+    pub(crate) region: String,
     #[serde(rename = "accessToken")]
-    access_token: String,
+    pub(crate) access_token: String,
     #[serde(rename = "expiresAt")]
-    expires_at: String,
+    pub(crate) expires_at: String,
     #[serde(rename = "clientId")]
     client_id: String,
     #[serde(rename = "clientSecret")]
@@ -512,7 +448,7 @@ struct SsoTokenCache {
     #[serde(rename = "registrationExpiresAt")]
     registration_expires_at: String,
     #[serde(rename = "refreshToken", skip_serializing_if = "Option::is_none")]
-    refresh_token: Option<String>,
+    pub(crate) refresh_token: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use cliclack::{intro, log, spinner};
+use crate::clients::argo_client::ArgoClient;
+use crate::clients::github_client::{GithubClient, GITOPS_REPO};
+use crate::models::argo::ArgoCdInstance;
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::github::{replace_version_file_tag, version_file_tag};
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::get_argo_app_statuses::{extract_app_name, extract_argo_instance};
+use crate::tasks::{Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+#[derive(Debug)]
+pub struct PromoteAppsTask;
+
+/// A change to one of an app's version files, ready to be committed
+struct VersionFileBump {
+    app: String,
+    path: String,
+    sha: String,
+    previous_tag: Option<String>,
+    tag: String,
+    content: String,
+}
+
+#[async_trait]
+impl Task for PromoteAppsTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Promote apps")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::AppsPromoted { apps, from, to, branch } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::AppsPromoted, params));
+        };
+        if from == to {
+            return Err(ArcError::UserInputError(format!("Can't promote apps from {} to itself", from)));
+        }
+
+        let token_goal = Goal::github_token_known();
+        if !state.contains(&token_goal) {
+            return Ok(GoalStatus::Needs(token_goal));
+        }
+        let client = GithubClient::new(state.get_github_token(&token_goal)?);
+
+        // The versions being promoted are whatever is currently deployed, rather than what git says should be
+        let argo_client = ArgoClient::new(ArgoCdInstance::from(from.as_str()), &config.argo)?;
+        let projects = config.argo.projects(None);
+        let deployed = argo_client.fetch_apps(&projects).await?;
+        let mut target_tags = Vec::new();
+        for app in apps {
+            let info = deployed.get(app)
+                .ok_or_else(|| ArcError::UserInputError(format!("{} isn't deployed in {}", app, from)))?;
+            if info.image_tag == "unknown" {
+                return Err(ArcError::UserInputError(format!("Unable to determine the image tag of {} in {}", app, from)));
+            }
+            target_tags.push((app, info.image_tag.clone()));
+        }
+
+        let progress = spinner();
+        progress.start(format!("Finding {} version files in {}...", to, GITOPS_REPO));
+        let base = client.fetch_repo(GITOPS_REPO).await?.default_branch;
+        let base_sha = client.fetch_branch_sha(GITOPS_REPO, &base).await?;
+        let paths = client.fetch_tree_paths(GITOPS_REPO, &base_sha).await?;
+
+        // An app may be deployed to several regions of an environment, each with its own version file
+        let to_instance = ArgoCdInstance::from(to.as_str());
+        let mut bumps = Vec::new();
+        let mut unchanged = Vec::new();
+        for (app, tag) in target_tags {
+            let version_files: Vec<&String> = paths.iter()
+                .filter(|path| extract_app_name(path, &projects).as_ref() == Some(app))
+                .filter(|path| extract_argo_instance(path) == Some(to_instance))
+                .collect();
+            if version_files.is_empty() {
+                progress.error(format!("No {} version files found for {}", to, app));
+                return Err(ArcError::UserInputError(format!("{} has no version file for {} in {}", app, to, GITOPS_REPO)));
+            }
+
+            for path in version_files {
+                let file = client.fetch_file(GITOPS_REPO, path, &base_sha).await?;
+                let content = file.decoded()?;
+                let previous_tag = version_file_tag(&content);
+                if previous_tag.as_ref() == Some(&tag) {
+                    unchanged.push(format!("{} is already at {}", path, tag));
+                    continue;
+                }
+
+                let content = replace_version_file_tag(&content, &tag)
+                    .ok_or_else(|| ArcError::UserInputError(format!("{} doesn't set an image tag", path)))?;
+                bumps.push(VersionFileBump {
+                    app: app.clone(),
+                    path: path.clone(),
+                    sha: file.sha,
+                    previous_tag,
+                    tag: tag.clone(),
+                    content,
+                });
+            }
+        }
+        progress.stop(format!("Found {} version files to update", bumps.len()));
+
+        for message in unchanged {
+            log::info(message)?;
+        }
+        if bumps.is_empty() {
+            return Err(ArcError::UserInputError(format!("{} already matches {}, so there's nothing to promote", to, from)));
+        }
+
+        // Each file is committed separately, since the contents API only updates one file at a time
+        let progress = spinner();
+        progress.start(format!("Committing version bumps to {}...", branch));
+        client.create_branch(GITOPS_REPO, branch, &base_sha).await?;
+
+        // Don't leave a half-promoted branch behind if any commit or the PR can't be created
+        for bump in &bumps {
+            let message = format!("Promote {} to {} in {}", bump.app, bump.tag, to);
+            if let Err(e) = client.update_file(GITOPS_REPO, branch, &bump.path, &bump.sha, &bump.content, &message).await {
+                progress.error(format!("Unable to commit {}", bump.path));
+                return Err(delete_branch_after_error(&client, branch, e).await);
+            }
+        }
+        progress.stop(format!("Committed {} version bumps to {}", bumps.len(), branch));
+
+        let title = format!("Promote {} to {}", apps.join(", "), to);
+        let pr = match client.create_pr(GITOPS_REPO, branch, &base, &title, &pr_body(&bumps, from, to)).await {
+            Ok(pr) => pr,
+            Err(e) => return Err(delete_branch_after_error(&client, branch, e).await),
+        };
+
+        let outro_text = OutroText::single(format!("Opened PR #{}", pr.number), pr.html_url);
+        Ok(GoalStatus::Completed(TaskResult::AppsPromoted(pr.number), outro_text))
+    }
+}
+
+/// Deletes a promotion branch that couldn't be turned into a PR, then returns the error that caused it.
+/// If the branch can't be deleted, its name is shown so that it can be cleaned up by hand.
+async fn delete_branch_after_error(client: &GithubClient, branch: &str, error: ArcError) -> ArcError {
+    if let Err(delete_error) = client.delete_branch(GITOPS_REPO, branch).await {
+        let _ = log::warning(format!(
+            "Unable to delete branch {} of {}, so it needs to be deleted manually: {}",
+            branch, GITOPS_REPO, delete_error
+        ));
+    }
+    error
+}
+
+fn pr_body(bumps: &[VersionFileBump], from: &str, to: &str) -> String {
+    let mut lines = vec![
+        format!("Promotes the versions currently deployed in {} to {}.", from, to),
+        String::new(),
+        format!("| App | Version file | {} | {} |", to, from),
+        "| --- | --- | --- | --- |".to_string(),
+    ];
+    lines.extend(bumps.iter().map(|bump| format!(
+        "| {} | `{}` | {} | {} |",
+        bump.app,
+        bump.path,
+        bump.previous_tag.as_deref().unwrap_or("-"),
+        bump.tag
+    )));
+    lines.push(String::new());
+    lines.push("Opened with `backend promote`.".to_string());
+    lines.join("\n")
+}
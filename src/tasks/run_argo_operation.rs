@@ -0,0 +1,213 @@
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use cliclack::{intro, note, select, spinner};
+use crate::clients::argo_client::ArgoClient;
+use crate::models::argo::{ArgoApplication, ArgoCdInstance, ArgoOperation};
+use crate::models::config::CliConfig;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::state::State;
+use crate::tasks::{confirm_production_change, Task, TaskResult};
+use crate::{GoalStatus, OutroText};
+
+const POLL_INTERVAL_SECS: u64 = 2;
+
+// Looking up the image tag of each revision renders its manifests, so only recent history is shown
+const HISTORY_LIMIT: usize = 10;
+
+#[derive(Debug)]
+pub struct RunArgoOperationTask;
+
+#[async_trait]
+impl Task for RunArgoOperationTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Run ArgoCD Operation")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::ArgoOperationCompleted { app, operation, timeout, env } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoOperationCompleted, params));
+        };
+
+        let instance_goal = Goal::argo_instance_selected(env.clone());
+        if !state.contains(&instance_goal) {
+            return Ok(GoalStatus::Needs(instance_goal));
+        }
+        let instance = *state.get_argo_instance(&instance_goal)?;
+        let client = ArgoClient::new(instance, &config.argo)?;
+
+        let current = client.fetch_app(app).await?;
+
+        let outro_msg = match operation {
+            ArgoOperation::Refresh { hard } => {
+                // Refreshing only compares the app against git, it doesn't change what's deployed
+                let progress = spinner();
+                progress.start(format!("{} of {} in progress...", operation.name(), app));
+                client.refresh_app(app, *hard).await?;
+                let refreshed = match wait_for_refresh(&client, app, *timeout).await {
+                    Ok(refreshed) => refreshed,
+                    Err(e) => {
+                        progress.error(format!("{} of {} failed", operation.name(), app));
+                        return Err(e);
+                    },
+                };
+                progress.stop(format!("{} of {} complete", operation.name(), app));
+                format!("{} ({})", app, refreshed.sync_status())
+            },
+            ArgoOperation::Sync { prune, dry_run } => {
+                if !dry_run && instance == ArgoCdInstance::Prod {
+                    confirm_production_change(app)?;
+                }
+
+                let previous_started_at = current.operation_started_at().map(String::from);
+                client.sync_app(app, *prune, *dry_run).await?;
+                let synced = follow_operation(&client, app, operation, previous_started_at, *timeout).await?;
+                format!("{} ({})", app, synced.sync_status())
+            },
+            ArgoOperation::Rollback(to) => {
+                let target = select_rollback_target(&client, &current, *to).await?;
+                if instance == ArgoCdInstance::Prod {
+                    confirm_production_change(app)?;
+                }
+
+                let previous_started_at = current.operation_started_at().map(String::from);
+                client.rollback_app(app, target).await?;
+                follow_operation(&client, app, operation, previous_started_at, *timeout).await?;
+                format!("{} (rolled back to history ID {})", app, target)
+            },
+        };
+
+        let key = format!("ArgoCD {} ({})", operation.name(), instance.name());
+        let outro_text = OutroText::single(key, outro_msg);
+        Ok(GoalStatus::Completed(TaskResult::ArgoOperationCompleted, outro_text))
+    }
+}
+
+/// Shows the app's deployment history along with the image tag of each revision, then returns the
+/// history ID to roll back to, prompting for one if it wasn't given
+async fn select_rollback_target(client: &ArgoClient, app: &ArgoApplication, to: Option<u64>) -> Result<u64, ArcError> {
+    let app_name = &app.metadata.name;
+    let history: Vec<_> = app.history().into_iter().take(HISTORY_LIMIT).collect();
+    let Some(deployed) = history.first() else {
+        return Err(ArcError::UserInputError(format!("ArgoCD application '{}' has no deployment history", app_name)));
+    };
+
+    let progress = spinner();
+    progress.start("Looking up deployed image tags...");
+    let mut rows = vec![format!("{:<6} {:<22} {:<12} {}", "ID", "Deployed At", "Revision", "Image Tag")];
+    let mut items = Vec::new();
+    for entry in &history {
+        let revision = entry.revision.as_deref().unwrap_or("unknown");
+        let image_tag = match entry.revision.as_deref() {
+            Some(revision) => client.fetch_revision_image_tag(app_name, revision).await?,
+            None => None,
+        }.unwrap_or_else(|| "unknown".to_string());
+
+        let deployed_at = entry.deployed_at.as_deref().unwrap_or("unknown");
+        let short_revision: String = revision.chars().take(10).collect();
+        let current_marker = if entry.id == deployed.id { " (current)" } else { "" };
+        rows.push(format!("{:<6} {:<22} {:<12} {}{}", entry.id, deployed_at, short_revision, image_tag, current_marker));
+        items.push((entry.id, format!("{} {}", deployed_at, image_tag)));
+    }
+    progress.stop(format!("Deployment history of {}", app_name));
+    note(format!("{} history", app_name), rows.join("\n"))?;
+
+    let target = match to {
+        Some(target) => target,
+        None => {
+            let mut menu = select("Select revision to roll back to");
+            for (id, label) in items.iter().filter(|(id, _)| *id != deployed.id) {
+                menu = menu.item(*id, format!("{} ({})", id, label), "");
+            }
+            menu.interact()?
+        },
+    };
+
+    if target == deployed.id {
+        return Err(ArcError::UserInputError(format!("History ID {} is already deployed", target)));
+    }
+    if !app.history().iter().any(|entry| entry.id == target) {
+        return Err(ArcError::UserInputError(format!("History ID {} not found for '{}'", target, app_name)));
+    }
+    Ok(target)
+}
+
+/// Polls an app until the operation that was just started finishes, showing its progress in a spinner.
+/// Gives up after the timeout, reporting the phase that the operation was last seen in.
+async fn follow_operation(
+    client: &ArgoClient,
+    app_name: &str,
+    operation: &ArgoOperation,
+    previous_started_at: Option<String>,
+    timeout: Duration,
+) -> Result<ArgoApplication, ArcError> {
+    let progress = spinner();
+    progress.start(format!("{} of {} starting...", operation.name(), app_name));
+
+    let deadline = Instant::now() + timeout;
+    let mut last_status = "not started".to_string();
+    loop {
+        if Instant::now() >= deadline {
+            let error_msg = format!(
+                "{} of {} timed out after {}s ({})",
+                operation.name(),
+                app_name,
+                timeout.as_secs(),
+                last_status
+            );
+            progress.error(&error_msg);
+            return Err(ArcError::CommandExecutionError(error_msg));
+        }
+
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        let app = client.fetch_app(app_name).await?;
+
+        // Until the new operation is picked up, the operation state still describes the previous one
+        if app.operation_started_at() == previous_started_at.as_deref() {
+            continue;
+        }
+
+        let message = app.operation_message().unwrap_or_default();
+        match app.operation_phase() {
+            Some("Succeeded") => {
+                progress.stop(format!("{} of {} succeeded", operation.name(), app_name));
+                return Ok(app);
+            },
+            Some(phase @ ("Failed" | "Error")) => {
+                let error_msg = format!("{} of {} {}: {}", operation.name(), app_name, phase.to_lowercase(), message);
+                progress.error(&error_msg);
+                return Err(ArcError::CommandExecutionError(error_msg));
+            },
+            phase => {
+                let phase = phase.unwrap_or("Pending");
+                last_status = format!("{}: {}", phase.to_lowercase(), message);
+                progress.set_message(format!("{} of {} {}", operation.name(), app_name, last_status));
+            },
+        }
+    }
+}
+
+async fn wait_for_refresh(client: &ArgoClient, app_name: &str, timeout: Duration) -> Result<ArgoApplication, ArcError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let app = client.fetch_app(app_name).await?;
+        if !app.is_refresh_pending() {
+            return Ok(app);
+        }
+        if Instant::now() >= deadline {
+            return Err(ArcError::CommandExecutionError(format!(
+                "Refresh of {} timed out after {}s (still pending, last sync status {})",
+                app_name,
+                timeout.as_secs(),
+                app.sync_status()
+            )));
+        }
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+    }
+}
@@ -1,6 +1,11 @@
+use std::fs::OpenOptions;
 use async_trait::async_trait;
-use cliclack::intro;
+use cliclack::{intro, log};
+use tokio::process::Command;
+use crate::clients::vault_client::VaultClient;
 use crate::models::errors::ArcError;
+use crate::models::rds::{DbRole, RdsInstance};
+use crate::models::vault::VaultDatabaseCredentials;
 use crate::models::goals::{Goal, GoalParams};
 use crate::{GoalStatus, OutroText};
 use crate::models::config::CliConfig;
@@ -20,7 +25,7 @@ impl Task for RunPgcliTask {
     async fn execute(
         &self,
         params: &GoalParams,
-        _config: &CliConfig,
+        config: &CliConfig,
         state: &State
     ) -> Result<GoalStatus, ArcError> {
         // Ensure that SSO token has not expired
@@ -29,10 +34,10 @@ impl Task for RunPgcliTask {
             return Ok(GoalStatus::Needs(sso_goal));
         }
 
-        // Extract aws_profile arg from params
-        let aws_profile = match params {
-            GoalParams::PgcliRunning { aws_profile } => aws_profile.clone(),
-            _ => None,
+        // Extract aws_profile and role args from params
+        let (aws_profile, role) = match params {
+            GoalParams::PgcliRunning { aws_profile, role } => (aws_profile.clone(), role.clone()),
+            _ => (None, None),
         };
 
         // If an RDS instance has not yet been selected, we need to wait for that goal to complete
@@ -43,6 +48,15 @@ impl Task for RunPgcliTask {
 
         // Retrieve selected RDS instance from state
         let rds_instance = state.get_rds_instance(&rds_selection_goal)?;
+
+        // Prefer short-lived credentials from Vault if a role was requested
+        if let Some(role) = role {
+            // The AWS profile was already selected as a prerequisite for selecting the RDS instance
+            let profile_info = state.get_aws_profile_info(&Goal::aws_profile_selected(aws_profile))?;
+            let client = VaultClient::new(&profile_info.account, config)?;
+            return run_with_vault_credentials(&client, rds_instance, &role).await;
+        }
+
         let rds_secret_name = rds_instance.secret_id().to_string();
 
         // If the password for this RDS instance has not yet been retrieved, we need to wait for that goal to complete
@@ -68,4 +82,64 @@ impl Task for RunPgcliTask {
         let outro_text = OutroText::single("Launching pgcli".to_string(), String::new());
         Ok(GoalStatus::Completed(TaskResult::PgcliCommand(cmd), outro_text))
     }
-}
\ No newline at end of file
+}
+
+async fn run_with_vault_credentials(
+    client: &VaultClient,
+    rds_instance: &RdsInstance,
+    role: &DbRole,
+) -> Result<GoalStatus, ArcError> {
+    // Clean up after any previous sessions that didn't exit gracefully
+    let orphaned = client.revoke_orphaned_leases().await?;
+    if orphaned > 0 {
+        log::info(format!("Revoked {} orphaned database lease(s)", orphaned))?;
+    }
+
+    let credentials = client.guarded_database_credentials(&rds_instance.vault_role(role)).await?;
+    log::info(format!(
+        "Issued {} credentials for {} (valid for {} minutes)",
+        role.name(),
+        rds_instance.name(),
+        credentials.lease_duration / 60,
+    ))?;
+
+    // Revoke the lease regardless of how pgcli exits, but report pgcli's failure ahead of the revoke's
+    let session = run_pgcli(rds_instance.host(), &credentials.data).await;
+    let revoked = client.guarded_revoke_lease(&credentials.lease_id).await;
+    if let (Err(_), Err(e)) = (&session, &revoked) {
+        log::warning(format!("Unable to revoke the {} credentials: {}", role.name(), e))?;
+    }
+    session?;
+    revoked?;
+
+    let outro_text = OutroText::single("pgcli session ended".to_string(), "credentials revoked".to_string());
+    Ok(GoalStatus::Completed(TaskResult::PgcliSessionEnded, outro_text))
+}
+
+async fn run_pgcli(host: &str, credentials: &VaultDatabaseCredentials) -> Result<(), ArcError> {
+    // Stdout is captured by the shell wrapper, so attach pgcli directly to the terminal instead
+    let tty = || OpenOptions::new().read(true).write(true).open("/dev/tty");
+
+    let mut child = Command::new("pgcli")
+        .args(["-h", host, "-U", &credentials.username])
+        .env("PGPASSWORD", &credentials.password)
+        .stdin(tty()?)
+        .stdout(tty()?)
+        .stderr(tty()?)
+        .spawn()
+        .map_err(|e| ArcError::CommandExecutionError(format!("Unable to launch pgcli: {}", e)))?;
+
+    // Ctrl+C is meant for pgcli (e.g. to cancel a query), so it mustn't terminate this process
+    // before the lease has been revoked
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status?,
+            _ = tokio::signal::ctrl_c() => continue,
+        }
+    };
+
+    if !status.success() {
+        return Err(ArcError::CommandExecutionError(format!("pgcli exited with {}", status)));
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use cliclack::{intro, select};
+use async_trait::async_trait;
+use crate::models::argo::ArgoCdInstance;
+use crate::models::aws_profile::{AwsAccount, AwsProfileInfo};
+use crate::models::errors::ArcError;
+use crate::models::goals::{GoalParams, GoalType};
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+use crate::tasks::{Task, TaskResult};
+
+#[derive(Debug)]
+pub struct SelectArgoInstanceTask;
+
+#[async_trait]
+impl Task for SelectArgoInstanceTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Select ArgoCD Instance")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        _config: &CliConfig,
+        _state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::ArgoInstanceSelected { env } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::ArgoInstanceSelected, params));
+        };
+
+        if let Some(env) = env {
+            let instance = ArgoCdInstance::from(env.as_str());
+            return Ok(GoalStatus::Completed(TaskResult::ArgoInstance(instance), OutroText::None));
+        }
+
+        // Infer the ArgoCD instance from the active AWS profile, if it belongs to an account with one
+        let inferred_instance = AwsProfileInfo::current().await
+            .filter(|profile| matches!(profile.account, AwsAccount::Dev | AwsAccount::Stage | AwsAccount::Prod))
+            .map(|profile| ArgoCdInstance::from(&profile));
+
+        let (instance, outro_text) = match inferred_instance {
+            Some(instance) => {
+                let key = "Inferred ArgoCD instance".to_string();
+                (instance, OutroText::single(key, instance.name().to_string()))
+            },
+            None => (prompt_for_argo_instance()?, OutroText::None),
+        };
+
+        Ok(GoalStatus::Completed(TaskResult::ArgoInstance(instance), outro_text))
+    }
+}
+
+fn prompt_for_argo_instance() -> Result<ArgoCdInstance, ArcError> {
+    let mut menu = select("Select ArgoCD instance");
+    for argo in ArgoCdInstance::all() {
+        menu = menu.item(argo.name().to_string(), argo.name(), "");
+    }
+
+    let argo_name = menu.interact()?;
+    Ok(ArgoCdInstance::from(argo_name.as_str()))
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+use async_trait::async_trait;
+use cliclack::{confirm, intro, note, password};
+use crate::tasks::{confirm_production_change, Task, TaskResult};
+use crate::clients::vault_client::VaultClient;
+use crate::models::errors::ArcError;
+use crate::models::goals::{Goal, GoalParams, GoalType};
+use crate::models::secret_display::masked_diff;
+use crate::models::vault::{VaultInstance, VaultWrite};
+use crate::{GoalStatus, OutroText};
+use crate::models::config::CliConfig;
+use crate::models::state::State;
+
+#[derive(Debug)]
+pub struct WriteVaultSecretTask;
+
+#[async_trait]
+impl Task for WriteVaultSecretTask {
+    fn print_intro(&self) -> Result<(), ArcError> {
+        intro("Write Vault Secret")?;
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        params: &GoalParams,
+        config: &CliConfig,
+        state: &State
+    ) -> Result<GoalStatus, ArcError> {
+        let GoalParams::VaultSecretWritten { path, write, aws_profile } = params else {
+            return Err(ArcError::invalid_goal_params(GoalType::VaultSecretWritten, params));
+        };
+
+        // Infer the AWS account, and therefore the Vault instance, from an AWS profile
+        let profile_goal = Goal::aws_profile_selected(aws_profile.clone());
+        if !state.contains(&profile_goal) {
+            return Ok(GoalStatus::Needs(profile_goal));
+        }
+        let profile_info = state.get_aws_profile_info(&profile_goal)?;
+        let client = VaultClient::new(&profile_info.account, config)?;
+
+        // Take the latest version from the metadata, since the data of a deleted version can't be read but
+        // check-and-set still needs its number. This lets the write be rejected if someone else changes it in the meantime.
+        let history = client.guarded_read_secret_history(path).await?;
+        let exists = history.is_some();
+        let current_version = history.iter().flatten().map(|info| info.version).max().unwrap_or(0);
+        let current = match current_version {
+            0 => None,
+            version => client.guarded_read_secret_version(path, Some(version)).await?,
+        };
+        let before = current.as_ref().map(|secret| secret.data.clone()).unwrap_or_default();
+        let latest_deleted = || ArcError::UserInputError(
+            format!("Vault secret '{}' does not exist or its latest version has been deleted", path)
+        );
+
+        let after = match write {
+            VaultWrite::Set(fields) => resolve_fields(fields)?,
+            VaultWrite::Patch(fields) => {
+                if current.is_none() {
+                    return Err(latest_deleted());
+                }
+                let mut data = before.clone();
+                data.extend(resolve_fields(fields)?);
+                data
+            },
+            VaultWrite::Delete => {
+                if current.is_none() {
+                    return Err(latest_deleted());
+                }
+                BTreeMap::new()
+            },
+            VaultWrite::Rollback(target) => {
+                // Restoring a secret whose latest version was deleted by mistake is the main use of rollback
+                if !exists {
+                    return Err(ArcError::UserInputError(format!("Vault secret '{}' does not exist", path)));
+                }
+                client.guarded_read_secret_version(path, Some(*target)).await?
+                    .ok_or_else(|| ArcError::UserInputError(
+                        format!("Version {} of Vault secret '{}' does not exist or has been deleted", target, path)
+                    ))?
+                    .data
+            },
+        };
+
+        // Summarize the changes without revealing any values
+        let changes = masked_diff(&before, &after);
+        if changes.is_empty() {
+            let outro_msg = format!("{} (version {}, unchanged)", path, current_version);
+            let outro_text = OutroText::single("Vault Secret".to_string(), outro_msg);
+            return Ok(GoalStatus::Completed(TaskResult::VaultSecretWritten(current_version), outro_text));
+        }
+        note(format!("{} ({})", path, client.instance().name()), changes.join("\n"))?;
+
+        let prompt = match write {
+            VaultWrite::Delete => format!("Delete version {} of '{}'?", current_version, path),
+            VaultWrite::Rollback(target) => format!("Roll back '{}' to version {}?", path, target),
+            _ => format!("Write these changes to '{}'?", path),
+        };
+        if !confirm(prompt).interact()? {
+            return Err(ArcError::UserInputError(format!("Write to Vault secret '{}' was cancelled", path)));
+        }
+        if matches!(client.instance(), VaultInstance::Prod) {
+            confirm_production_change(path)?;
+        }
+
+        let (version, outro_msg) = match write {
+            VaultWrite::Delete => {
+                client.guarded_delete_secret_version(path, current_version).await?;
+                (current_version, format!("{} (version {} deleted)", path, current_version))
+            },
+            VaultWrite::Rollback(target) => {
+                // Rolling back writes the old data as a new version, so history is preserved
+                let version = client.guarded_write_secret(path, &after, current_version).await?;
+                (version, format!("{} (version {}, restored from version {})", path, version, target))
+            },
+            _ => {
+                let version = client.guarded_write_secret(path, &after, current_version).await?;
+                (version, format!("{} (version {})", path, version))
+            },
+        };
+
+        let outro_text = OutroText::single("Vault Secret".to_string(), outro_msg);
+        Ok(GoalStatus::Completed(TaskResult::VaultSecretWritten(version), outro_text))
+    }
+}
+
+/// Fills in any fields that were provided without a value by prompting the user, keeping the input hidden
+fn resolve_fields(fields: &[(String, Option<String>)]) -> Result<BTreeMap<String, String>, ArcError> {
+    let mut data = BTreeMap::new();
+    for (key, value) in fields {
+        let value = match value {
+            Some(v) => v.clone(),
+            None => password(format!("Value for '{}'", key)).mask('▪').interact()?,
+        };
+        data.insert(key.clone(), value);
+    }
+    Ok(data)
+}